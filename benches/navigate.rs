@@ -0,0 +1,138 @@
+use aoc2020::generate::Rng;
+use aoc2020::geometry::{Direction, Map, Point, Traversable};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Tile(bool);
+
+impl From<Tile> for Traversable {
+    fn from(tile: Tile) -> Self {
+        if tile.0 {
+            Traversable::Obstructed
+        } else {
+            Traversable::Free
+        }
+    }
+}
+
+/// A square maze of scattered walls, open at two opposite corners.
+fn maze(size: usize) -> Map<Tile> {
+    let mut rng = Rng::new(2020);
+    let mut map = Map::new(size, size);
+    map.for_each_point_mut(|tile, _| *tile = Tile(rng.chance(0.3)));
+    map[(0, 0)] = Tile(false);
+    map[(size - 1, size - 1)] = Tile(false);
+    map
+}
+
+/// `Map::navigate` as it was before its rewrite, for comparison: its heap is ordered by path
+/// cost rather than f-score, so it's really a uniform-cost search, and it scans the heap to
+/// check whether a point is already queued.
+fn baseline_navigate(map: &Map<Tile>, from: Point, to: Point) -> Option<Vec<Direction>> {
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    struct Node {
+        cost: u32,
+        position: Point,
+    }
+
+    impl Ord for Node {
+        fn cmp(&self, other: &Node) -> Ordering {
+            other
+                .cost
+                .cmp(&self.cost)
+                .then_with(|| self.position.cmp(&other.position))
+        }
+    }
+
+    impl PartialOrd for Node {
+        fn partial_cmp(&self, other: &Node) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    let mut open_set = BinaryHeap::new();
+    open_set.push(Node {
+        cost: 0,
+        position: from,
+    });
+    let mut came_from = HashMap::new();
+    let mut cheapest_path_cost = HashMap::new();
+    cheapest_path_cost.insert(from, 0_u32);
+    let mut total_cost_guess = HashMap::new();
+    total_cost_guess.insert(from, (to - from).manhattan() as u32);
+
+    while let Some(Node { cost, position }) = open_set.pop() {
+        if position == to {
+            let mut current = position;
+            let mut path = Vec::new();
+            while let Some((direction, predecessor)) = came_from.remove(&current) {
+                current = predecessor;
+                path.push(direction);
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        for direction in Direction::iter() {
+            let neighbor = position + direction;
+            if !map.in_bounds(neighbor)
+                || Traversable::from(map[neighbor]) == Traversable::Obstructed
+            {
+                continue;
+            }
+            let tentative = cost + 1;
+            if tentative
+                < cheapest_path_cost
+                    .get(&neighbor)
+                    .cloned()
+                    .unwrap_or(u32::MAX)
+            {
+                came_from.insert(neighbor, (direction, position));
+                cheapest_path_cost.insert(neighbor, tentative);
+                total_cost_guess.insert(neighbor, tentative + (to - neighbor).manhattan() as u32);
+                if !open_set.iter().any(|node| node.position == neighbor) {
+                    open_set.push(Node {
+                        cost: tentative,
+                        position: neighbor,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+fn navigate(c: &mut Criterion) {
+    let map = maze(100);
+    let from = Point::new(0, 0);
+    let to = Point::new(99, 99);
+    assert_eq!(
+        baseline_navigate(&map, from, to).map(|path| path.len()),
+        map.navigate(from, to).map(|path| path.len())
+    );
+    let mut group = c.benchmark_group("navigate 100x100");
+    group.bench_function("baseline", |b| b.iter(|| baseline_navigate(&map, from, to)));
+    group.bench_function("a_star", |b| b.iter(|| map.navigate(from, to)));
+    group.finish();
+}
+
+fn heuristic(c: &mut Criterion) {
+    let map = maze(300);
+    let from = Point::new(0, 0);
+    let to = Point::new(299, 299);
+    let cost = |tile: &Tile, _| if tile.0 { None } else { Some(1) };
+    let mut group = c.benchmark_group("300x300");
+    group.bench_function("a_star manhattan", |b| {
+        b.iter(|| map.a_star(from, &[to], cost, |point| (to - point).manhattan() as u32))
+    });
+    group.bench_function("a_star zero", |b| {
+        b.iter(|| map.a_star(from, &[to], cost, |_| 0))
+    });
+    group.bench_function("dijkstra", |b| b.iter(|| map.dijkstra(from, cost)));
+    group.finish();
+}
+
+criterion_group!(benches, navigate, heuristic);
+criterion_main!(benches);
@@ -3,11 +3,11 @@ use aoc2020::parse;
 use std::path::Path;
 use thiserror::Error;
 
-pub fn part1(input: &Path) -> Result<(), Error> \{
+pub fn part1(input: &Path) -> Result<usize, Error> \{
     unimplemented!()
 }
 
-pub fn part2(_input: &Path) -> Result<(), Error> \{
+pub fn part2(_input: &Path) -> Result<usize, Error> \{
     unimplemented!()
 }
 
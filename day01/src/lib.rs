@@ -1,70 +1,75 @@
 use aoc2020::parse;
+use aoc2020::sums::KSum;
 
-use std::collections::HashSet;
 use std::path::Path;
 use thiserror::Error;
 
-fn product_of_2020_pair(entries: HashSet<usize>) -> Option<usize> {
-    let first = entries.iter().find(|n| entries.contains(&(2020 - *n)));
+/// What to search the expense report for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The sum the entries must add up to.
+    pub target: i64,
+    /// How many entries must add up to it; by default 2 in part 1 and 3 in part 2.
+    pub k: Option<usize>,
+}
 
-    match first {
-        Some(n) => Some(n * (2020 - n)),
-        None => None,
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            target: 2020,
+            k: None,
+        }
     }
 }
 
-fn product_of_2020_triple(entries: HashSet<usize>) -> Option<usize> {
-    // Cartesian product of the entries set with itself
-    let mut zipped = entries
-        .iter()
-        .flat_map(|a| entries.iter().map(move |b| (a, b)))
-        .filter(|(a, b)| a != b);
-
-    // The first check prevents unsigned int underflow
-    let first_pair = zipped.find(|(a, b)| 2020 > *a + *b && entries.contains(&(2020 - *a - *b)));
-
-    match first_pair {
-        Some((a, b)) => Some(a * b * (2020 - a - b)),
-        None => None,
-    }
+fn product_of_sum(entries: &[i64], k: usize, target: i64) -> Option<i64> {
+    KSum::new(k, target)
+        .find(entries)
+        .map(|combination| combination.product())
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let entries: HashSet<usize> = parse(input)?.collect();
+pub fn part1_with(input: &Path, options: &Options) -> Result<i64, Error> {
+    let entries: Vec<i64> = parse(input)?.collect();
+    product_of_sum(&entries, options.k.unwrap_or(2), options.target).ok_or(Error::NoSolution)
+}
 
-    match product_of_2020_pair(entries) {
-        Some(n) => println!("{}", n),
-        None => println!("Solution not found."),
-    }
-    Ok(())
+pub fn part2_with(input: &Path, options: &Options) -> Result<i64, Error> {
+    let entries: Vec<i64> = parse(input)?.collect();
+    product_of_sum(&entries, options.k.unwrap_or(3), options.target).ok_or(Error::NoSolution)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let entries: HashSet<usize> = parse(input)?.collect();
+pub fn part1(input: &Path) -> Result<i64, Error> {
+    part1_with(input, &Options::default())
+}
 
-    match product_of_2020_triple(entries) {
-        Some(n) => println!("{}", n),
-        None => println!("Solution not found."),
-    }
-    Ok(())
+pub fn part2(input: &Path) -> Result<i64, Error> {
+    part2_with(input, &Options::default())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const ENTRIES: [i64; 6] = [1721, 979, 366, 299, 675, 1456];
+
     #[test]
     fn test_product_of_2020_pair() {
-        let entries = [1721, 979, 366, 299, 675, 1456].iter().cloned().collect();
-        let expected = Some(514579);
-        assert_eq!(product_of_2020_pair(entries), expected);
+        assert_eq!(product_of_sum(&ENTRIES, 2, 2020), Some(514579));
     }
 
     #[test]
     fn test_product_of_2020_triple() {
-        let entries = [1721, 979, 366, 299, 675, 1456].iter().cloned().collect();
-        let expected = Some(241861950);
-        assert_eq!(product_of_2020_triple(entries), expected);
+        assert_eq!(product_of_sum(&ENTRIES, 3, 2020), Some(241861950));
+    }
+
+    #[test]
+    fn test_other_targets() {
+        assert_eq!(product_of_sum(&ENTRIES, 2, 1345), Some(979 * 366));
+        assert_eq!(
+            product_of_sum(&ENTRIES, 4, 3365),
+            Some(1721 * 979 * 366 * 299)
+        );
+        assert_eq!(product_of_sum(&ENTRIES, 2, 1000), None);
     }
 }
 
@@ -72,4 +77,6 @@ mod tests {
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("Solution not found")]
+    NoSolution,
 }
@@ -1,49 +1,35 @@
-use aoc2020::config::Config;
-use day01::{part1, part2};
+use day01::{part1_with, part2_with, Options};
 
+use aoc2020::runner::RunArgs;
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
 
 const DAY: u8 = 1;
 
 #[derive(StructOpt, Debug)]
-struct RunArgs {
-    /// input file
-    #[structopt(parse(from_os_str))]
-    input: Option<PathBuf>,
+struct Args {
+    #[structopt(flatten)]
+    run: RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// the sum the entries must add up to
+    #[structopt(long, default_value = "2020")]
+    target: i64,
 
-    /// run part 2
+    /// how many entries must add up to it; by default 2 in part 1 and 3 in part 2
     #[structopt(long)]
-    part2: bool,
-}
-
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                Ok(config.input_for(DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    k: Option<usize>,
 }
 
 fn main() -> Result<()> {
-    color_eyre::install()?;
-    let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
-    }
-    if args.part2 {
-        part2(&input_path)?;
-    }
-    Ok(())
+    let args = Args::from_args();
+    let options = Options {
+        target: args.target,
+        k: args.k,
+    };
+    aoc2020::runner::run_with_args(
+        DAY,
+        args.run,
+        |input| part1_with(input, &options),
+        |input| part2_with(input, &options),
+    )
 }
@@ -71,20 +71,14 @@ where
     entries.filter(|e| valid_toboggan_password(e)).count()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let input_iter = parse::<PasswordEntry>(input)?;
-    let count = valid_password_count(input_iter);
-
-    println!("The answer to part one is: {}", count);
-    Ok(())
+    Ok(valid_password_count(input_iter))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let input_iter = parse::<PasswordEntry>(input)?;
-    let count = valid_toboggan_password_count(input_iter);
-
-    println!("The answer to part two is: {}", count);
-    Ok(())
+    Ok(valid_toboggan_password_count(input_iter))
 }
 
 #[derive(Debug, Error)]
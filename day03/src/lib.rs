@@ -1,5 +1,6 @@
-use aoc2020::parse;
+use aoc2020::geometry::{Edges, Map, Point, YAxis};
 
+use std::convert::TryFrom;
 use std::path::Path;
 use thiserror::Error;
 
@@ -9,35 +10,19 @@ struct Slope {
 }
 
 impl Slope {
-    fn trees_in_map(&self, map: &Vec<String>) -> usize {
-        let mut map_iter = map.into_iter();
-        let line_length = map_iter.next().unwrap().chars().count();
-
-        let mut count = 0;
-        let mut pos = 0;
-
-        loop {
-            for _ in 1..self.down {
-                map_iter.next();
-            }
-
-            match map_iter.next() {
-                Some(s) => {
-                    if s == "" {
-                        return count;
-                    }
-                    pos = (pos + self.right) % line_length;
-                    if s.chars().nth(pos as usize).unwrap() == '#' {
-                        count += 1;
-                    }
-                }
-                None => return count,
-            }
-        }
+    /// Count the trees passed on the way from the top left corner to the bottom of a map which
+    /// repeats forever to the right, and whose rows are numbered top down.
+    fn trees_in_map(&self, map: &Map<char>) -> usize {
+        debug_assert_eq!(map.y_axis(), YAxis::Down);
+        let step = Point::new(self.right as i32, self.down as i32);
+        map.view(Edges::WRAP_X)
+            .ray(Point::new(0, 0), step)
+            .filter(|(_, tile)| **tile == '#')
+            .count()
     }
 }
 
-fn product_of_trees_in_maps(map: Vec<String>) -> usize {
+fn product_of_trees_in_maps(map: Map<char>) -> usize {
     let slopes = [
         Slope { down: 1, right: 1 },
         Slope { down: 1, right: 3 },
@@ -49,22 +34,14 @@ fn product_of_trees_in_maps(map: Vec<String>) -> usize {
     slopes.iter().map(|s| s.trees_in_map(&map)).product()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let map: Vec<String> = parse::<String>(input)?.take_while(|s| s != "").collect();
-    println!(
-        "The answer to part one is {}",
-        Slope { down: 1, right: 3 }.trees_in_map(&map)
-    );
-    Ok(())
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    let map = Map::<char>::try_from(input)?.with_y_axis(YAxis::Down);
+    Ok(Slope { down: 1, right: 3 }.trees_in_map(&map))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let map: Vec<String> = parse::<String>(input)?.take_while(|s| s != "").collect();
-    println!(
-        "The answer to part one is {}",
-        product_of_trees_in_maps(map)
-    );
-    Ok(())
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    let map = Map::<char>::try_from(input)?.with_y_axis(YAxis::Down);
+    Ok(product_of_trees_in_maps(map))
 }
 
 #[derive(Debug, Error)]
@@ -76,39 +53,51 @@ pub enum Error {
 #[cfg(test)]
 #[test]
 fn test_trees_in_map() {
-    let example = &vec![
-        "..##.........##.........##.........##.........##.........##.......".to_string(),
-        "#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..".to_string(),
-        ".#....#..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#.".to_string(),
-        "..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#".to_string(),
-        ".#...##..#..#...##..#..#...##..#..#...##..#..#...##..#..#...##..#.".to_string(),
-        "..#.##.......#.##.......#.##.......#.##.......#.##.......#.##.....".to_string(),
-        ".#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#".to_string(),
-        ".#........#.#........#.#........#.#........#.#........#.#........#".to_string(),
-        "#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...".to_string(),
-        "#...##....##...##....##...##....##...##....##...##....##...##....#".to_string(),
-        ".#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#".to_string(),
-    ];
+    let example = Map::<char>::try_from(
+        [
+            "..##.........##.........##.........##.........##.........##.......",
+            "#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..",
+            ".#....#..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#.",
+            "..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#",
+            ".#...##..#..#...##..#..#...##..#..#...##..#..#...##..#..#...##..#.",
+            "..#.##.......#.##.......#.##.......#.##.......#.##.......#.##.....",
+            ".#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#",
+            ".#........#.#........#.#........#.#........#.#........#.#........#",
+            "#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...",
+            "#...##....##...##....##...##....##...##....##...##....##...##....#",
+            ".#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#",
+        ]
+        .join("\n")
+        .as_str(),
+    )
+    .unwrap()
+    .with_y_axis(YAxis::Down);
 
     let expected = 7;
-    assert_eq!(Slope { down: 1, right: 3 }.trees_in_map(example), expected);
+    assert_eq!(Slope { down: 1, right: 3 }.trees_in_map(&example), expected);
 }
 
 #[test]
 fn test_product_of_trees_in_maps() {
-    let example = vec![
-        "..##.........##.........##.........##.........##.........##.......".to_string(),
-        "#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..".to_string(),
-        ".#....#..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#.".to_string(),
-        "..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#".to_string(),
-        ".#...##..#..#...##..#..#...##..#..#...##..#..#...##..#..#...##..#.".to_string(),
-        "..#.##.......#.##.......#.##.......#.##.......#.##.......#.##.....".to_string(),
-        ".#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#".to_string(),
-        ".#........#.#........#.#........#.#........#.#........#.#........#".to_string(),
-        "#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...".to_string(),
-        "#...##....##...##....##...##....##...##....##...##....##...##....#".to_string(),
-        ".#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#".to_string(),
-    ];
+    let example = Map::<char>::try_from(
+        [
+            "..##.........##.........##.........##.........##.........##.......",
+            "#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..#...#...#..",
+            ".#....#..#..#....#..#..#....#..#..#....#..#..#....#..#..#....#..#.",
+            "..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#..#.#...#.#",
+            ".#...##..#..#...##..#..#...##..#..#...##..#..#...##..#..#...##..#.",
+            "..#.##.......#.##.......#.##.......#.##.......#.##.......#.##.....",
+            ".#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#.#.#.#....#",
+            ".#........#.#........#.#........#.#........#.#........#.#........#",
+            "#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...#.##...#...",
+            "#...##....##...##....##...##....##...##....##...##....##...##....#",
+            ".#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#.#..#...#.#",
+        ]
+        .join("\n")
+        .as_str(),
+    )
+    .unwrap()
+    .with_y_axis(YAxis::Down);
 
     let expected = 336;
     assert_eq!(product_of_trees_in_maps(example), expected);
@@ -120,20 +120,16 @@ impl FromStr for Passport {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let complete_count = parse_newline_sep::<Passport>(input)?
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    Ok(parse_newline_sep::<Passport>(input)?
         .filter(|p| p.complete())
-        .count();
-    println!("The answer to part one is {}", complete_count);
-    Ok(())
+        .count())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let valid_count = parse_newline_sep::<Passport>(input)?
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    Ok(parse_newline_sep::<Passport>(input)?
         .filter(|p| p.complete() && p.valid())
-        .count();
-    println!("The answer to part twp is {}", valid_count);
-    Ok(())
+        .count())
 }
 
 #[derive(Debug, Error)]
@@ -55,18 +55,17 @@ impl Seat {
     }
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let max_id = parse::<String>(input)?
         .take_while(|s| s != "")
         .map(|s| Seat::from_str(&s).unwrap().id())
         .max()
         .unwrap();
 
-    println!("The answer to part one is {}", max_id);
-    Ok(())
+    Ok(max_id)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let mut ids = parse::<String>(input)?
         .take_while(|s| s != "")
         .map(|s| Seat::from_str(&s).unwrap().id())
@@ -77,8 +76,7 @@ pub fn part2(input: &Path) -> Result<(), Error> {
 
     for i in 1..ids.len() - 1 {
         if ids[i] != first_id + i {
-            println!("The answer to part one is {}", first_id + i);
-            return Ok(());
+            return Ok(first_id + i);
         }
     }
 
@@ -24,22 +24,20 @@ fn total_completely_yessed(group_response: String) -> usize {
         .count()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let group_responses_count: usize = parse_newline_sep::<String>(input)
         .unwrap()
         .map(|group_response| total_yessed(group_response))
         .sum();
-    println!("The answer to part one is {}", group_responses_count);
-    Ok(())
+    Ok(group_responses_count)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let group_responses_count: usize = parse_newline_sep::<String>(input)
         .unwrap()
         .map(|group_response| total_completely_yessed(group_response))
         .sum();
-    println!("The answer to part two is {}", group_responses_count);
-    Ok(())
+    Ok(group_responses_count)
 }
 
 #[derive(Debug, Error)]
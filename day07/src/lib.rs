@@ -156,22 +156,14 @@ fn total_contained_by_shiny_gold(bag_map: BagMap) -> usize {
     contained_by_rec(&bag_map, "shiny gold".to_string())
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let bag_map: BagMap = parse::<ParsedEntry>(input)?.collect();
-    println!(
-        "The answer to part one is: {}",
-        total_containing_shiny_gold(bag_map)
-    );
-    Ok(())
+    Ok(total_containing_shiny_gold(bag_map))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let bag_map: BagMap = parse::<ParsedEntry>(input)?.collect();
-    println!(
-        "The answer to part two is: {}",
-        total_contained_by_shiny_gold(bag_map)
-    );
-    Ok(())
+    Ok(total_contained_by_shiny_gold(bag_map))
 }
 
 #[derive(Debug, Error)]
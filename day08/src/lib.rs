@@ -1,4 +1,7 @@
-use aoc2020::parse;
+use aoc2020::{
+    parse,
+    search::{dfs_with, ByKey},
+};
 
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -65,65 +68,76 @@ fn execute(program: Vec<Command>) -> isize {
     }
 }
 
-fn execute_with_flip(program: &Vec<Command>, flipped_instr: usize) -> Option<isize> {
-    if program[flipped_instr].operator == "acc" {
-        return None;
-    }
-
-    let mut instruction_ptr = 0;
-    let mut acc = 0;
-
-    let mut executed_indices = HashSet::<usize>::new();
-
-    loop {
-        if instruction_ptr == program.len() {
-            return Some(acc);
-        }
-
-        if executed_indices.contains(&(instruction_ptr as usize)) {
-            return None;
-        }
-
-        executed_indices.insert(instruction_ptr as usize);
+/// Where a program run has got to, and whether it has already flipped an instruction.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct RunState {
+    instruction_ptr: usize,
+    acc: isize,
+    flipped: bool,
+}
 
-        let command = &program[instruction_ptr as usize];
-        match command.operator.as_str() {
-            "acc" => acc += command.operand,
+impl RunState {
+    fn step(self, command: &Command, flip: bool) -> RunState {
+        let operator = match (command.operator.as_str(), flip) {
+            ("jmp", true) => "nop",
+            ("nop", true) => "jmp",
+            (operator, _) => operator,
+        };
+        let mut next = RunState {
+            instruction_ptr: self.instruction_ptr + 1,
+            flipped: self.flipped || flip,
+            ..self
+        };
+        match operator {
+            "acc" => next.acc += command.operand,
             "jmp" => {
-                if instruction_ptr as usize != flipped_instr {
-                    instruction_ptr = (command.operand + instruction_ptr as isize) as usize - 1
-                }
-            }
-            "nop" => {
-                if instruction_ptr as usize == flipped_instr {
-                    instruction_ptr = (command.operand + instruction_ptr as isize) as usize - 1
-                }
+                next.instruction_ptr = (command.operand + self.instruction_ptr as isize) as usize
             }
+            "nop" => (),
             _ => panic!("Invalid operator"),
         }
-        instruction_ptr += 1;
+        next
     }
 }
 
 fn find_corrupted_instr(program: Vec<Command>) -> isize {
-    (0..program.len())
-        .find_map(|n| execute_with_flip(&program, n))
-        .expect("Could not find corrupted instruction")
+    let start = RunState {
+        instruction_ptr: 0,
+        acc: 0,
+        flipped: false,
+    };
+    let successors = |state: &RunState| {
+        let mut next = Vec::with_capacity(2);
+        if let Some(command) = program.get(state.instruction_ptr) {
+            next.push((state.step(command, false), 1));
+            if !state.flipped && command.operator != "acc" {
+                next.push((state.step(command, true), 1));
+            }
+        }
+        next
+    };
+    // the accumulator doesn't affect where a run goes, so it mustn't distinguish visited states
+    let visited = ByKey::new(|state: &RunState| (state.instruction_ptr, state.flipped));
+
+    dfs_with(
+        start,
+        successors,
+        |state| state.instruction_ptr == program.len(),
+        visited,
+    )
+    .expect("Could not find corrupted instruction")
+    .goal()
+    .acc
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<isize, Error> {
     let program = parse::<Command>(input)?.collect();
-    println!("The answer to part one is {}", execute(program));
-    Ok(())
+    Ok(execute(program))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<isize, Error> {
     let program = parse::<Command>(input)?.collect();
-    println!(
-        "The answer to part two is {}",
-        find_corrupted_instr(program)
-    );
-    Ok(())
+    Ok(find_corrupted_instr(program))
 }
 
 #[derive(Debug, Error)]
@@ -156,3 +170,40 @@ fn test_find_corrupted_instr() {
 
     assert_eq!(find_corrupted_instr(example), 8);
 }
+
+#[test]
+fn test_generated_boot_code() {
+    // the accumulator once the program runs off its end, with the instruction at `flip`
+    // flipped, or else as it first repeats an instruction
+    fn run(program: &[Command], flip: Option<usize>) -> Result<isize, isize> {
+        let mut state = RunState {
+            instruction_ptr: 0,
+            acc: 0,
+            flipped: false,
+        };
+        let mut visited = HashSet::new();
+        while let Some(command) = program.get(state.instruction_ptr) {
+            if !visited.insert(state.instruction_ptr) {
+                return Err(state.acc);
+            }
+            state = state.step(command, flip == Some(state.instruction_ptr));
+        }
+        Ok(state.acc)
+    }
+
+    for seed in 0..5 {
+        let input = aoc2020::generate::generate(8, seed, 1).unwrap();
+        let parse = || -> Vec<Command> { input.lines().map(|s| s.parse().unwrap()).collect() };
+        let program = parse();
+
+        let looped = run(&program, None).expect_err(&format!("seed {} doesn't loop", seed));
+        let fixed: Vec<isize> = (0..program.len())
+            .filter(|idx| program[*idx].operator != "acc")
+            .filter_map(|idx| run(&program, Some(idx)).ok())
+            .collect();
+        assert_eq!(fixed.len(), 1, "seed {} has {} fixes", seed, fixed.len());
+
+        assert_eq!(execute(parse()), looped, "seed {}", seed);
+        assert_eq!(find_corrupted_instr(parse()), fixed[0], "seed {}", seed);
+    }
+}
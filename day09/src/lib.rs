@@ -1,90 +1,88 @@
 use aoc2020::parse;
-use std::collections::BTreeSet;
+use aoc2020::sums::{KSum, RangeSum, Strategy};
 use std::path::Path;
 use thiserror::Error;
 
-fn summed_by_preamble(n: usize, preamble: &BTreeSet<usize>) -> bool {
-    let mut min_iter = preamble.iter();
-    let mut max_iter = preamble.iter().rev();
-
-    let mut min = min_iter.next().unwrap();
-    let mut max = max_iter.next().unwrap();
-
-    while max > min {
-        let result = min + max;
-        if result == n {
-            return true;
-        } else if result < n {
-            min = min_iter.next().unwrap();
-        } else {
-            max = max_iter.next().unwrap();
-        }
-    }
-    false
+/// How to search the XMAS data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// How many distinct preamble numbers must add up to each following number.
+    pub k: usize,
+    /// The number whose weakness part 2 looks for, instead of the first invalid one.
+    pub target: Option<i64>,
+    /// How many numbers precede each one checked.
+    pub preamble_length: usize,
 }
 
-fn first_non_sum(xs: &Vec<usize>, preamble_length: usize) -> usize {
-    let mut preamble: BTreeSet<usize> = xs[..preamble_length].iter().map(|n| *n).collect();
-    for n in preamble_length..xs.len() {
-        if !summed_by_preamble(xs[n], &preamble) {
-            return xs[n];
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            k: 2,
+            target: None,
+            preamble_length: 25,
         }
-        preamble.remove(&xs[n - preamble_length]);
-        preamble.insert(xs[n]);
     }
-    panic!("There are no invalid entries")
 }
 
-fn find_weakness(xs: Vec<usize>, preamble_length: usize) -> usize {
-    let target = first_non_sum(&xs, preamble_length);
-
-    let mut lower_bound_iter = xs.iter();
-    let mut upper_bound_iter = xs.iter().skip(1);
+/// Every number which isn't a sum of `k` of the `preamble_length` numbers before it.
+fn non_sums(xs: &[i64], preamble_length: usize, k: usize) -> impl Iterator<Item = i64> + '_ {
+    (preamble_length..xs.len())
+        .filter(move |&n| {
+            KSum::new(k, xs[n])
+                .with_strategy(Strategy::Hash)
+                .find(&xs[n - preamble_length..n])
+                .is_none()
+        })
+        .map(move |n| xs[n])
+}
 
-    let mut lower = lower_bound_iter.next().unwrap();
-    let mut upper = upper_bound_iter.next().unwrap();
+fn first_non_sum(xs: &[i64], preamble_length: usize, k: usize) -> Option<i64> {
+    non_sums(xs, preamble_length, k).next()
+}
 
-    let mut rolling_total = lower + upper;
+/// The sum of the smallest and largest numbers of the first run of at least two numbers which
+/// adds up to `target`.
+fn find_weakness(xs: &[i64], target: i64) -> Option<i64> {
+    let range = RangeSum::new(target)
+        .with_min_len(2)
+        .stream(xs.iter().copied())
+        .next()?;
+    let run = &xs[range];
+    Some(run.iter().min()? + run.iter().max()?)
+}
 
-    let mut range = BTreeSet::<usize>::new();
-    range.insert(*lower);
-    range.insert(*upper);
+pub fn part1_with(input: &Path, options: &Options) -> Result<i64, Error> {
+    let xs: Vec<i64> = parse(input)?.collect();
+    first_non_sum(&xs, options.preamble_length, options.k).ok_or(Error::NoInvalidEntry)
+}
 
-    loop {
-        if rolling_total == target {
-            return range.iter().next().unwrap() + range.iter().rev().next().unwrap();
-        } else if rolling_total < target {
-            upper = upper_bound_iter.next().unwrap();
-            rolling_total += upper;
-            range.insert(*upper);
-        } else {
-            rolling_total -= lower;
-            range.remove(lower);
-            lower = lower_bound_iter.next().unwrap();
+pub fn part2_with(input: &Path, options: &Options) -> Result<i64, Error> {
+    let xs: Vec<i64> = parse(input)?.collect();
+    let target = match options.target {
+        Some(target) => target,
+        None => {
+            first_non_sum(&xs, options.preamble_length, options.k).ok_or(Error::NoInvalidEntry)?
         }
-    }
+    };
+    find_weakness(&xs, target).ok_or(Error::NoWeakness)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part one is {}",
-        first_non_sum(&parse(input)?.collect(), 25)
-    );
-    Ok(())
+pub fn part1(input: &Path) -> Result<i64, Error> {
+    part1_with(input, &Options::default())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part two is {}",
-        find_weakness(parse(input)?.collect(), 25)
-    );
-    Ok(())
+pub fn part2(input: &Path) -> Result<i64, Error> {
+    part2_with(input, &Options::default())
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("There are no invalid entries")]
+    NoInvalidEntry,
+    #[error("No run of numbers adds up to the target")]
+    NoWeakness,
 }
 
 #[cfg(test)]
@@ -94,7 +92,9 @@ fn test_first_non_sum() {
         35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
     ];
 
-    assert_eq!(first_non_sum(&example, 5), 127);
+    assert_eq!(first_non_sum(&example, 5, 2), Some(127));
+    // no three of 35, 20, 15, 25 and 47 add up to 40
+    assert_eq!(first_non_sum(&example, 5, 3), Some(40));
 }
 
 #[test]
@@ -103,5 +103,23 @@ fn test_find_weakness() {
         35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309, 576,
     ];
 
-    assert_eq!(find_weakness(example, 5), 62);
+    assert_eq!(find_weakness(&example, 127), Some(62));
+    assert_eq!(find_weakness(&example, 1), None);
+}
+
+#[test]
+fn test_generated_data() {
+    for seed in 0..5 {
+        let input = aoc2020::generate::generate(9, seed, 1).unwrap();
+        let xs: Vec<i64> = input.lines().map(|s| s.parse().unwrap()).collect();
+        let invalid: Vec<i64> = non_sums(&xs, 25, 2).collect();
+        assert_eq!(
+            invalid.len(),
+            1,
+            "seed {} has invalid numbers {:?}",
+            seed,
+            invalid
+        );
+        assert!(find_weakness(&xs, invalid[0]).is_some());
+    }
 }
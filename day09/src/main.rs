@@ -1,49 +1,40 @@
-use aoc2020::config::Config;
-use day09::{part1, part2};
+use day09::{part1_with, part2_with, Options};
 
+use aoc2020::runner::RunArgs;
 use color_eyre::eyre::Result;
-use std::path::PathBuf;
 use structopt::StructOpt;
 
 const DAY: u8 = 9;
 
 #[derive(StructOpt, Debug)]
-struct RunArgs {
-    /// input file
-    #[structopt(parse(from_os_str))]
-    input: Option<PathBuf>,
+struct Args {
+    #[structopt(flatten)]
+    run: RunArgs,
 
-    /// skip part 1
-    #[structopt(long = "no-part1")]
-    no_part1: bool,
+    /// how many distinct preamble numbers must add up to each following number
+    #[structopt(long, default_value = "2")]
+    k: usize,
 
-    /// run part 2
+    /// find the weakness for this number in part 2, instead of the first invalid one
     #[structopt(long)]
-    part2: bool,
-}
+    target: Option<i64>,
 
-impl RunArgs {
-    fn input(&self) -> Result<PathBuf> {
-        match self.input {
-            None => {
-                let config = Config::load()?;
-                Ok(config.input_for(DAY))
-            }
-            Some(ref path) => Ok(path.clone()),
-        }
-    }
+    /// how many numbers precede each one checked
+    #[structopt(long, default_value = "25")]
+    preamble: usize,
 }
 
 fn main() -> Result<()> {
-    color_eyre::install()?;
-    let args = RunArgs::from_args();
-    let input_path = args.input()?;
-
-    if !args.no_part1 {
-        part1(&input_path)?;
-    }
-    if args.part2 {
-        part2(&input_path)?;
-    }
-    Ok(())
+    let args = Args::from_args();
+    let options = Options {
+        k: args.k,
+        target: args.target,
+        preamble_length: args.preamble,
+    };
+    aoc2020::runner::run_with_args(
+        DAY,
+        args.run,
+        |input| part1_with(input, &options),
+        |input| part2_with(input, &options),
+    )
 }
@@ -23,10 +23,9 @@ fn joltage_difference_counts(joltages: &HashSet<usize>) -> (usize, usize) {
     (one_jolt_jumps, three_jolt_jumps)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let (one_diffs, three_diffs) = joltage_difference_counts(&parse(input)?.collect());
-    println!("The answer to part one is {}", one_diffs * three_diffs);
-    Ok(())
+    Ok(one_diffs * three_diffs)
 }
 
 fn arrangements_count(joltages: BTreeSet<usize>) -> usize {
@@ -48,10 +47,9 @@ fn arrangements_count(joltages: BTreeSet<usize>) -> usize {
     *adapter_combo_map.get(&0).unwrap()
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let joltages = parse(input)?.collect();
-    println!("The answer to part two is {}", arrangements_count(joltages));
-    Ok(())
+    Ok(arrangements_count(joltages))
 }
 
 #[derive(Debug, Error)]
@@ -1,45 +1,18 @@
-use aoc2020::parse;
+use aoc2020::{
+    geometry::{Compass, Point, Turn},
+    parse,
+};
 
 use std::path::Path;
 use std::str::FromStr;
 use thiserror::Error;
 
 #[derive(Clone, Copy)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-    Left,
-    Right,
-    Forward,
-}
-
-impl Direction {
-    fn next_left(&self) -> Direction {
-        match &self {
-            Self::North => Self::West,
-            Self::West => Self::South,
-            Self::South => Self::East,
-            Self::East => Self::North,
-            _ => panic!("Invalid use of next_left on a non-cardinal direction"),
-        }
-    }
-
-    fn next_right(&self) -> Direction {
-        match &self {
-            Self::North => Self::East,
-            Self::West => Self::North,
-            Self::South => Self::West,
-            Self::East => Self::South,
-            _ => panic!("Invalid use of next_right on a non-cardinal direction"),
-        }
-    }
-}
-
-struct Instruction {
-    dir: Direction,
-    amount: usize,
+enum Instruction {
+    Move(Compass, i32),
+    /// Degrees clockwise.
+    Turn(i32),
+    Forward(i32),
 }
 
 impl FromStr for Instruction {
@@ -47,112 +20,72 @@ impl FromStr for Instruction {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut string_iter = s.chars();
-
-        let dir = match string_iter.next() {
-            Some('N') => Direction::North,
-            Some('S') => Direction::South,
-            Some('E') => Direction::East,
-            Some('W') => Direction::West,
-            Some('L') => Direction::Left,
-            Some('F') => Direction::Forward,
-            Some('R') => Direction::Right,
-            _ => return Err("Unable to parse direction".to_string()),
-        };
+        let letter = string_iter
+            .next()
+            .ok_or_else(|| "Unable to parse direction".to_string())?;
 
         let amount = string_iter
             .collect::<String>()
-            .parse::<usize>()
+            .parse::<i32>()
             .expect("Unable to parse amount");
 
-        Ok(Self {
-            dir: dir,
-            amount: amount,
-        })
+        if let Some(compass) = Compass::from_letter(letter) {
+            Ok(Self::Move(compass, amount))
+        } else if let Some(turn) = Turn::from_letter(letter) {
+            Ok(Self::Turn(turn.degrees(amount)))
+        } else if letter == 'F' {
+            Ok(Self::Forward(amount))
+        } else {
+            Err("Unable to parse direction".to_string())
+        }
     }
 }
 
 fn manhattan_dist(instrs: impl Iterator<Item = Instruction>) -> usize {
-    let mut x = 0 as isize;
-    let mut y = 0 as isize;
-    let mut current_dir = Direction::East;
-
-    let mut go_dir = |i: Instruction| {
-        match i.dir {
-            Direction::North => y += i.amount as isize,
-            Direction::South => y -= i.amount as isize,
-            Direction::East => x += i.amount as isize,
-            Direction::West => x -= i.amount as isize,
-            _ => {} // not reachable
-        }
-    };
+    let mut ship = Point::default();
+    let mut heading = Compass::East;
 
     for i in instrs {
-        match i.dir {
-            Direction::Forward => go_dir(Instruction {
-                dir: current_dir,
-                amount: i.amount,
-            }),
-            Direction::Left => {
-                for _ in 0..(i.amount / 90) {
-                    current_dir = current_dir.next_left()
-                }
-            }
-            Direction::Right => {
-                for _ in 0..(i.amount / 90) {
-                    current_dir = current_dir.next_right()
-                }
+        match i {
+            Instruction::Move(compass, amount) => ship = ship + Point::from(compass) * amount,
+            Instruction::Turn(degrees) => {
+                heading = heading
+                    .turn(degrees)
+                    .expect("Turns must be multiples of 45 degrees")
             }
-            _ => go_dir(i),
+            Instruction::Forward(amount) => ship = ship + Point::from(heading) * amount,
         }
     }
-    (x.abs() + y.abs()) as usize
+    ship.manhattan() as usize
 }
 
 fn waypoint_manhattan_dist(instrs: impl Iterator<Item = Instruction>) -> usize {
-    let mut x = 0 as isize;
-    let mut y = 0 as isize;
-    let mut waypoint = (10 as isize, 1 as isize);
+    let mut ship = Point::default();
+    let mut waypoint = Point::new(10, 1);
 
     for i in instrs {
-        match i.dir {
-            Direction::North => waypoint = (waypoint.0, waypoint.1 + i.amount as isize),
-            Direction::South => waypoint = (waypoint.0, waypoint.1 - i.amount as isize),
-            Direction::East => waypoint = (waypoint.0 + i.amount as isize, waypoint.1),
-            Direction::West => waypoint = (waypoint.0 - i.amount as isize, waypoint.1),
-            Direction::Forward => {
-                x += waypoint.0 * i.amount as isize;
-                y += waypoint.1 * i.amount as isize;
-            }
-            Direction::Left => {
-                for _ in 0..(i.amount / 90) {
-                    waypoint = (-waypoint.1, waypoint.0)
-                }
+        match i {
+            Instruction::Move(compass, amount) => {
+                waypoint = waypoint + Point::from(compass) * amount
             }
-            Direction::Right => {
-                for _ in 0..(i.amount / 90) {
-                    waypoint = (waypoint.1, -waypoint.0)
-                }
+            Instruction::Turn(degrees) => {
+                waypoint = waypoint
+                    .rotate(degrees)
+                    .expect("Waypoint rotations must be multiples of 90 degrees")
             }
+            Instruction::Forward(amount) => ship = ship + waypoint * amount,
         }
     }
 
-    (x.abs() + y.abs()) as usize
+    ship.manhattan() as usize
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part one is {}",
-        manhattan_dist(parse(input)?)
-    );
-    Ok(())
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    Ok(manhattan_dist(parse(input)?))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part one is {}",
-        waypoint_manhattan_dist(parse(input)?)
-    );
-    Ok(())
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    Ok(waypoint_manhattan_dist(parse(input)?))
 }
 
 #[derive(Debug, Error)]
@@ -1,3 +1,4 @@
+use aoc2020::math::{self, crt};
 use aoc2020::parse;
 
 use std::path::Path;
@@ -14,34 +15,21 @@ fn earliest_departure(timestamp: usize, ids: Vec<usize>) -> usize {
     earliest_id * remaining_minutes(earliest_id)
 }
 
-fn earliest_contiguous_departure(id_list: String) -> usize {
-    let mut buses: Vec<(usize, usize)> = id_list
-        .split(",")
+/// The earliest timestamp at which each bus departs as many minutes after it as its position in
+/// the list: bus `id` at position `offset` needs `timestamp ≡ -offset (mod id)`.
+#[tracing::instrument]
+fn earliest_contiguous_departure(id_list: String) -> Result<usize, Error> {
+    let buses = id_list
+        .split(',')
         .enumerate()
-        .filter_map(|(x, y)| match y.parse::<usize>().ok() {
-            Some(n) => Some((x, n)),
-            None => None,
-        })
-        .collect();
-
-    // Something something Chinese remainder theorem
-    buses.sort_by(|(_, id_a), (_, id_b)| id_a.cmp(id_b));
-
-    let (offset, mut cycle_length) = buses.pop().unwrap();
+        .filter_map(|(offset, id)| Some((-(offset as i128), id.parse::<u64>().ok()?)));
 
-    let mut timestamp = cycle_length - offset;
-
-    while buses.len() > 0 {
-        timestamp += cycle_length;
-
-        let matches = buses.iter().filter(|(pos, id)| (timestamp + pos) % id == 0);
-        cycle_length = matches.fold(cycle_length, |acc, (_, id)| acc * id);
-        buses.retain(|(pos, id)| (timestamp + pos) % id != 0);
-    }
-    timestamp
+    let solution = crt(buses)?;
+    tracing::trace!(?solution);
+    Ok(solution.residue as usize)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let mut input_iter = parse::<String>(input)?;
     let timestamp = input_iter.next().unwrap().parse()?;
     let ids = input_iter
@@ -51,19 +39,11 @@ pub fn part1(input: &Path) -> Result<(), Error> {
         .filter_map(|s| s.parse().ok())
         .collect();
 
-    println!(
-        "The answer to part one is {}",
-        earliest_departure(timestamp, ids)
-    );
-    Ok(())
+    Ok(earliest_departure(timestamp, ids))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part two is {}",
-        earliest_contiguous_departure(parse(input)?.nth(1).unwrap())
-    );
-    Ok(())
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    earliest_contiguous_departure(parse(input)?.nth(1).unwrap())
 }
 
 #[derive(Debug, Error)]
@@ -72,6 +52,8 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("Could not parse integer")]
     ParseInt(#[from] std::num::ParseIntError),
+    #[error("No departure satisfies every bus: {0}")]
+    Math(#[from] math::Error),
 }
 
 #[cfg(test)]
@@ -85,17 +67,21 @@ fn test_earliest_departure() {
 #[test]
 fn test_earliest_contiguous_departures() {
     let ids = "17,x,13,19".to_string();
-    assert_eq!(earliest_contiguous_departure(ids), 3417);
+    assert_eq!(earliest_contiguous_departure(ids).unwrap(), 3417);
 
     let ids = "67,7,59,61".to_string();
-    assert_eq!(earliest_contiguous_departure(ids), 754018);
+    assert_eq!(earliest_contiguous_departure(ids).unwrap(), 754018);
 
     let ids = "67,x,7,59,61".to_string();
-    assert_eq!(earliest_contiguous_departure(ids), 779210);
+    assert_eq!(earliest_contiguous_departure(ids).unwrap(), 779210);
 
     let ids = "67,7,x,59,61".to_string();
-    assert_eq!(earliest_contiguous_departure(ids), 1261476);
+    assert_eq!(earliest_contiguous_departure(ids).unwrap(), 1261476);
 
     let ids = "1789,37,47,1889".to_string();
-    assert_eq!(earliest_contiguous_departure(ids), 1202161486);
+    assert_eq!(earliest_contiguous_departure(ids).unwrap(), 1202161486);
+
+    // bus 4 would have to leave at an odd time, one minute after bus 2
+    let ids = "2,4".to_string();
+    assert!(earliest_contiguous_departure(ids).is_err());
 }
@@ -121,20 +121,16 @@ fn execute_floating_bitmask_program(program: Vec<String>) -> u64 {
     memory.values().sum::<u64>()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part one is {}",
-        execute_bitmask_program(parse(input)?.take_while(|s| s != "").collect())
-    );
-    Ok(())
+pub fn part1(input: &Path) -> Result<u64, Error> {
+    Ok(execute_bitmask_program(
+        parse(input)?.take_while(|s| s != "").collect(),
+    ))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part two is {}",
-        execute_floating_bitmask_program(parse(input)?.take_while(|s| s != "").collect())
-    );
-    Ok(())
+pub fn part2(input: &Path) -> Result<u64, Error> {
+    Ok(execute_floating_bitmask_program(
+        parse(input)?.take_while(|s| s != "").collect(),
+    ))
 }
 
 #[derive(Debug, Error)]
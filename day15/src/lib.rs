@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 
+#[tracing::instrument(skip(initial))]
 fn nth_turn(initial: &Vec<usize>, n: usize) -> usize {
     if n <= initial.len() {
         return initial[n - 1];
@@ -16,29 +17,27 @@ fn nth_turn(initial: &Vec<usize>, n: usize) -> usize {
     }
 
     (initial.len() + 1..=n).fold(initial[initial.len() - 1], |acc, next| {
+        if next % 1_000_000 == 0 {
+            tracing::debug!(turn = next, distinct = last_seen.len());
+        }
         let next_value = next - 1 - last_seen.get(&acc).unwrap_or(&(next - 1));
         last_seen.insert(acc, next - 1);
         next_value
     })
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part one is {}",
-        nth_turn(&parse::<CommaSep<usize>>(input)?.flatten().collect(), 2020)
-    );
-    Ok(())
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    Ok(nth_turn(
+        &parse::<CommaSep<usize>>(input)?.flatten().collect(),
+        2020,
+    ))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    println!(
-        "The answer to part one is {}",
-        nth_turn(
-            &parse::<CommaSep<usize>>(input)?.flatten().collect(),
-            30000000
-        )
-    );
-    Ok(())
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    Ok(nth_turn(
+        &parse::<CommaSep<usize>>(input)?.flatten().collect(),
+        30000000,
+    ))
 }
 
 #[derive(Debug, Error)]
@@ -61,8 +61,6 @@ fn departure_fields(
         })
         .collect();
 
-    println!("{:?}", valid_tickets);
-
     let mut remaining_fields: HashSet<&TicketField> = fields.iter().collect();
 
     let mut field_indexes = HashMap::<usize, &String>::new();
@@ -98,7 +96,7 @@ fn departure_fields(
         .collect()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let chunks: Vec<Vec<String>> = parse_newline_sep::<String>(input)?
         .map(|chunk| chunk.split('\n').map(|split| split.to_string()).collect())
         .collect();
@@ -120,14 +118,10 @@ pub fn part1(input: &Path) -> Result<(), Error> {
         })
         .collect();
 
-    println!(
-        "The answer to part one is {}",
-        ticket_field_validity_rate(fields, tickets)
-    );
-    Ok(())
+    Ok(ticket_field_validity_rate(fields, tickets))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let chunks: Vec<Vec<String>> = parse_newline_sep::<String>(input)?
         .map(|chunk| chunk.split('\n').map(|split| split.to_string()).collect())
         .collect();
@@ -163,15 +157,11 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         })
         .collect();
 
-    println!(
-        "The answer to part two is {}",
-        departure_fields(fields, own_ticket, tickets)
-            .iter()
-            .filter(|(k, _)| k.starts_with("departure"))
-            .map(|(_, v)| *v)
-            .product::<usize>()
-    );
-    Ok(())
+    Ok(departure_fields(fields, own_ticket, tickets)
+        .iter()
+        .filter(|(k, _)| k.starts_with("departure"))
+        .map(|(_, v)| *v)
+        .product())
 }
 
 #[derive(Debug, Error)]
@@ -1,103 +1,50 @@
+use aoc2020::automaton::Automaton;
+use aoc2020::geometry::PointN;
 use aoc2020::parse;
 
 use std::collections::HashSet;
 use std::path::Path;
 use thiserror::Error;
 
-// Handling of arbitrary dimensions adapted from https://rosettacode.org/wiki/Cartesian_product_of_two_or_more_lists#Rust
-fn neighbours(point: &Vec<isize>) -> HashSet<Vec<isize>> {
-    point
-        .iter()
-        .fold(
-            vec![Vec::<isize>::new()],
-            |points: Vec<Vec<isize>>, new_dimension| {
-                points
-                    .iter()
-                    .flat_map(|p| {
-                        let neighbouring_points: Vec<Vec<isize>> = (-1..=1)
-                            .map(|i| {
-                                let mut new_points = p.clone();
-                                new_points.push(new_dimension + i);
-                                new_points
-                            })
-                            .collect();
-                        neighbouring_points
-                    })
-                    .collect()
-            },
-        )
-        .iter()
-        .filter(|p| *p != point)
-        .cloned()
-        .collect()
-}
-
-fn cycle_cubes(cubes: HashSet<Vec<isize>>, n: usize) -> HashSet<Vec<isize>> {
-    fn cycle(cubes: HashSet<Vec<isize>>) -> HashSet<Vec<isize>> {
-        (&cubes)
-            .iter()
-            .fold(cubes.clone(), |set, next_point| {
-                set.union(&neighbours(&(*next_point).to_vec()))
-                    .cloned()
-                    .collect()
-            })
-            .iter()
-            .filter(|c| {
-                let previously_active_neighbours = neighbours(*c)
-                    .iter()
-                    .filter(|c| (&cubes).contains(*c))
-                    .count();
-
-                if (&cubes).contains(*c) {
-                    previously_active_neighbours == 2 || previously_active_neighbours == 3
-                } else {
-                    previously_active_neighbours == 3
-                }
-            })
-            .cloned()
-            .collect()
-    }
-
-    (0..n).fold(cubes, |prev_cubes, _| cycle(prev_cubes))
+fn cycle_cubes<const D: usize>(cubes: HashSet<PointN<D>>, n: usize) -> HashSet<PointN<D>> {
+    let automaton = Automaton::new(
+        |point: &PointN<D>| point.neighbours().collect::<Vec<_>>(),
+        |active: &bool, neighbours: &[bool]| {
+            let active_neighbours = neighbours.iter().filter(|n| **n).count();
+            active_neighbours == 3 || (*active && active_neighbours == 2)
+        },
+    );
+    automaton.run(cubes, n)
 }
 
-fn parse_initial_state(lines: Vec<String>, four_dimensions: bool) -> HashSet<Vec<isize>> {
+/// The initial state is a slice through the first two dimensions.
+fn parse_initial_state<const D: usize>(lines: Vec<String>) -> HashSet<PointN<D>> {
     let mut point_set = HashSet::new();
-    for i in 0..lines.len() {
-        let active_in_line = lines[i]
+    for (i, line) in lines.iter().enumerate() {
+        let active_in_line = line
             .chars()
             .enumerate()
             .filter(|(_, c)| *c == '#')
             .map(|(idx, _)| idx);
 
         for j in active_in_line {
-            if four_dimensions {
-                point_set.insert(vec![j as isize, i as isize, 0, 0]);
-            } else {
-                point_set.insert(vec![j as isize, i as isize, 0]);
-            }
+            let mut point = PointN::default();
+            point[0] = j as i32;
+            point[1] = i as i32;
+            point_set.insert(point);
         }
     }
     point_set
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let initial_state =
-        parse_initial_state(parse(input)?.take_while(|s| *s != "").collect(), false);
-    println!(
-        "The answer to part one is {}",
-        cycle_cubes(initial_state, 6).len()
-    );
-    Ok(())
+pub fn part1(input: &Path) -> Result<usize, Error> {
+    let initial_state = parse_initial_state::<3>(parse(input)?.take_while(|s| *s != "").collect());
+    Ok(cycle_cubes(initial_state, 6).len())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
-    let initial_state = parse_initial_state(parse(input)?.take_while(|s| *s != "").collect(), true);
-    println!(
-        "The answer to part one is {}",
-        cycle_cubes(initial_state, 6).len()
-    );
-    Ok(())
+pub fn part2(input: &Path) -> Result<usize, Error> {
+    let initial_state = parse_initial_state::<4>(parse(input)?.take_while(|s| *s != "").collect());
+    Ok(cycle_cubes(initial_state, 6).len())
 }
 
 #[derive(Debug, Error)]
@@ -109,42 +56,44 @@ pub enum Error {
 #[cfg(test)]
 #[test]
 fn test_neighbours() {
-    let example = &vec![0, 0];
+    let example = PointN::new([0, 0]);
 
     assert_eq!(
-        neighbours(example),
+        example.neighbours().collect::<HashSet<_>>(),
         vec![
-            vec![-1, -1],
-            vec![-1, 0],
-            vec![-1, 1],
-            vec![0, -1],
-            vec![0, 1],
-            vec![1, -1],
-            vec![1, 0],
-            vec![1, 1],
+            [-1, -1],
+            [-1, 0],
+            [-1, 1],
+            [0, -1],
+            [0, 1],
+            [1, -1],
+            [1, 0],
+            [1, 1],
         ]
-        .iter()
-        .cloned()
+        .into_iter()
+        .map(PointN::new)
         .collect()
     )
 }
 
 #[test]
 fn test_cycle_cubes() {
-    let example = parse_initial_state(
-        vec![".#.".to_string(), "..#".to_string(), "###".to_string()],
-        false,
-    );
+    let example = parse_initial_state::<3>(vec![
+        ".#.".to_string(),
+        "..#".to_string(),
+        "###".to_string(),
+    ]);
 
     assert_eq!(cycle_cubes(example, 6).len(), 112);
 }
 
 #[test]
 fn test_cycle_cubes_4d() {
-    let example = parse_initial_state(
-        vec![".#.".to_string(), "..#".to_string(), "###".to_string()],
-        true,
-    );
+    let example = parse_initial_state::<4>(vec![
+        ".#.".to_string(),
+        "..#".to_string(),
+        "###".to_string(),
+    ]);
 
     assert_eq!(cycle_cubes(example, 6).len(), 848);
 }
@@ -121,24 +121,22 @@ fn evaluate_expression(expression: String, evaluator: &dyn Fn(String) -> usize)
     )
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let sum: usize = parse(input)?
         .take_while(|s| *s != "")
         .map(|expression| evaluate_expression(expression, &evaluate_simple_expression))
         .sum();
 
-    println!("The answer to part one is {}", sum);
-    Ok(())
+    Ok(sum)
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let sum: usize = parse(input)?
         .take_while(|s| *s != "")
         .map(|expression| evaluate_expression(expression, &evaluate_advanced_simple_expression))
         .sum();
 
-    println!("The answer to part two is {}", sum);
-    Ok(())
+    Ok(sum)
 }
 
 #[derive(Debug, Error)]
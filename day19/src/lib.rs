@@ -270,6 +270,7 @@ fn matching_rules(rules: HashMap<usize, Rule>, messages: Vec<String>) -> usize {
         .count()
 }
 
+#[tracing::instrument(skip(rules, messages))]
 fn matching_rules_substituted(rules: &mut HashMap<usize, Rule>, messages: Vec<String>) -> usize {
     rules.insert(8, Rule::Repeat(vec![rules[&42].clone()]));
 
@@ -286,6 +287,11 @@ fn matching_rules_substituted(rules: &mut HashMap<usize, Rule>, messages: Vec<St
 
     while expansions.len() > 0 {
         let next = expansions.pop().unwrap();
+        tracing::trace!(
+            pending = expansions.len(),
+            found = regex_expansions.len(),
+            "expanding"
+        );
 
         if next.iter().all(|r| r.fully_expanded()) {
             let string_rep = next
@@ -329,6 +335,7 @@ fn matching_rules_substituted(rules: &mut HashMap<usize, Rule>, messages: Vec<St
         }
     }
 
+    tracing::debug!(expansions = regex_expansions.len(), "expanded rule 0");
     let regexps: Vec<Regex> = regex_expansions
         .iter()
         .map(|s| Regex::new(s).unwrap())
@@ -340,32 +347,24 @@ fn matching_rules_substituted(rules: &mut HashMap<usize, Rule>, messages: Vec<St
         .count()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let mut split_input = parse_newline_sep::<String>(input)?
         .map(|chunk| chunk.split('\n').map(|split| split.to_string()).collect());
 
     let rules = parse_rules(split_input.next().unwrap());
     let messages = split_input.next().unwrap();
 
-    println!(
-        "The answer to part one is {}",
-        matching_rules(rules, messages)
-    );
-    Ok(())
+    Ok(matching_rules(rules, messages))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let mut split_input = parse_newline_sep::<String>(input)?
         .map(|chunk| chunk.split('\n').map(|split| split.to_string()).collect());
 
     let mut rules = parse_rules(split_input.next().unwrap());
     let messages = split_input.next().unwrap();
 
-    println!(
-        "The answer to part two is {}",
-        matching_rules_substituted(&mut rules, messages)
-    );
-    Ok(())
+    Ok(matching_rules_substituted(&mut rules, messages))
 }
 
 #[derive(Debug, Error)]
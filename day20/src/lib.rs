@@ -1,124 +1,78 @@
+use aoc2020::geometry::{Direction, Map, Orientation, Pattern};
 use aoc2020::parse_newline_sep;
 
-use lazy_static::lazy_static;
-use regex::Regex;
 use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
 use std::path::Path;
 use thiserror::Error;
 
-lazy_static! {
-    static ref MONSTER: Regex = Regex::new(r"#....##....##....###").unwrap();
-    static ref MONSTER_BOTTOM: Regex = Regex::new(r".#..#..#..#..#..#...").unwrap();
-}
+const SEA_MONSTER: &str = "
+                  #
+#    ##    ##    ###
+ #  #  #  #  #  #
+";
 
-const MONSTER_HEAD_RELATIVE_INDEX: usize = 18;
-const MAGIC_MONSTER_NUMBER: usize = 15; // The number of tiles a monster lives on
+fn sea_monster() -> Pattern<char> {
+    Pattern::parse(SEA_MONSTER, ' ').unwrap()
+}
 
 #[derive(Debug, Clone, PartialEq)]
 struct Tile {
     id: usize,
-    contents: Vec<String>,
+    contents: Map<char>,
 }
 
 impl Tile {
     fn edges(&self) -> [String; 4] {
         [
-            self.contents[0].clone(),
-            self.contents[&self.contents.len() - 1].clone(),
-            self.contents
-                .iter()
-                .fold(String::new(), |mut string, next_line| {
-                    string.push(next_line.chars().next().unwrap());
-                    string
-                })
-                .clone(),
-            self.contents
-                .iter()
-                .fold(String::new(), |mut string, next_line| {
-                    string.push(next_line.chars().last().unwrap());
-                    string
-                })
-                .clone(),
+            self.top_edge(),
+            self.bottom_edge(),
+            self.left_edge(),
+            self.right_edge(),
         ]
     }
 
     fn trim(&mut self) {
-        self.contents.pop();
-        self.contents.remove(0);
-        for l in &mut self.contents {
-            l.pop();
-            l.remove(0);
-        }
+        self.contents = self.contents.strip_border(1);
     }
 
     fn top_edge(&self) -> String {
-        self.contents[0].clone()
+        self.contents.edge(Direction::Up).into_iter().collect()
     }
 
     fn bottom_edge(&self) -> String {
-        self.contents[self.contents.len() - 1].clone()
+        self.contents.edge(Direction::Down).into_iter().collect()
     }
 
     fn left_edge(&self) -> String {
-        self.contents
-            .iter()
-            .map(|l| l.chars().next().unwrap())
-            .collect()
+        self.contents.edge(Direction::Left).into_iter().collect()
     }
 
     fn right_edge(&self) -> String {
-        self.contents
-            .iter()
-            .map(|l| l.chars().last().unwrap())
-            .collect()
+        self.contents.edge(Direction::Right).into_iter().collect()
     }
 
-    fn flip(&self) -> Self {
-        Self {
-            id: self.id,
-            contents: self
-                .contents
-                .iter()
-                .map(|line| reverse_string(line))
-                .collect(),
-        }
+    fn rows(&self) -> Vec<String> {
+        self.contents
+            .to_string()
+            .lines()
+            .map(String::from)
+            .collect()
     }
 
-    fn rotate(&self, times: usize) -> Self {
-        // Performs times clockwise rotations
-        (0..times).fold(self.clone(), |tile, _| {
-            let mut next = Vec::new();
-            for _ in 0..self.contents.len() {
-                next.push(String::new());
-            }
-
-            for i in 0..self.contents.len() {
-                for j in 0..self.contents.len() {
-                    next[j].push(tile.contents[i].chars().nth(j).unwrap())
-                }
-            }
-
-            next = next.iter().map(|s| reverse_string(s)).collect();
-
-            Self {
+    fn orientations(&self) -> Vec<Tile> {
+        self.contents
+            .orientations()
+            .map(|contents| Tile {
                 id: self.id,
-                contents: next,
-            }
-        })
+                contents: contents.to_map(),
+            })
+            .collect()
     }
+}
 
-    fn orientations(&self) -> Vec<Tile> {
-        vec![
-            self.clone(),
-            self.rotate(1),
-            self.rotate(2),
-            self.rotate(3),
-            self.flip(),
-            self.flip().rotate(1),
-            self.flip().rotate(2),
-            self.flip().rotate(3),
-        ]
-    }
+fn parse_map(lines: &[String]) -> Map<char> {
+    Map::try_from(lines.join("\n").as_str()).unwrap()
 }
 
 fn parse_tile(mut lines: Vec<String>) -> Tile {
@@ -130,7 +84,7 @@ fn parse_tile(mut lines: Vec<String>) -> Tile {
             .collect::<String>()
             .parse()
             .unwrap(),
-        contents: lines,
+        contents: parse_map(&lines),
     }
 }
 
@@ -183,7 +137,7 @@ fn find_corner_pieces(tiles: &Vec<Tile>) -> Vec<Tile> {
         .collect()
 }
 
-fn assemble_jigsaw(tiles: Vec<Tile>) -> Vec<String> {
+fn assemble_jigsaw(tiles: Vec<Tile>) -> Map<char> {
     let tile_map: HashMap<usize, Tile> = tiles.iter().map(|t| (t.id, t.clone())).collect();
 
     let edge_map = edge_map(&tiles);
@@ -298,67 +252,31 @@ fn assemble_jigsaw(tiles: Vec<Tile>) -> Vec<String> {
 
     let mut result = vec![];
 
-    for tile_line in 0..tile_row_length {
-        for row in 0..assembled[tile_line][0].contents.len() {
+    for tile_line in &assembled {
+        let rows: Vec<Vec<String>> = tile_line.iter().map(Tile::rows).collect();
+        for row in 0..rows[0].len() {
             result.push(
-                assembled[tile_line]
-                    .iter()
-                    .map(|tile| tile.contents[row].clone())
+                rows.iter()
+                    .map(|tile| tile[row].as_str())
                     .collect::<String>(),
             )
         }
     }
 
-    result
-}
-
-fn has_any_sea_monsters(tile: &Tile) -> bool {
-    tile.contents
-        .iter()
-        .skip(1)
-        .any(|line| MONSTER.is_match(line))
+    parse_map(&result)
 }
 
 fn water_roughness(tiles: Vec<Tile>) -> usize {
-    let jigsaw = Tile {
-        contents: assemble_jigsaw(tiles),
-        id: 0,
-    };
-
-    let hash_tile_count: usize = jigsaw
-        .contents
-        .iter()
-        .map(|line| line.chars().filter(|c| c == &'#').count())
-        .sum();
-
-    let correct_orientation = jigsaw
-        .orientations()
-        .into_iter()
-        // Technically might yield false positives, but doesn't for given or test inputs
-        .find(|tile| has_any_sea_monsters(tile))
-        .unwrap();
+    let jigsaw = assemble_jigsaw(tiles);
+    let hash_tile_count = jigsaw.iter().filter(|c| **c == '#').count();
 
-    let monster_tile_count: usize = (1..correct_orientation.contents.len() - 1)
-        .map(|line| {
-            MONSTER
-                .find_iter(&correct_orientation.contents[line])
-                .filter(|monster_match| {
-                    let index = monster_match.start();
-                    MONSTER_BOTTOM.is_match_at(&correct_orientation.contents[line + 1], index)
-                        && correct_orientation.contents[line - 1]
-                            .chars()
-                            .nth(index + MONSTER_HEAD_RELATIVE_INDEX)
-                            .unwrap()
-                            == '#'
-                })
-                .count()
-        })
-        .sum::<usize>()
-        * MAGIC_MONSTER_NUMBER;
-    hash_tile_count - monster_tile_count
+    // Only the correct orientation of the image has any monsters in it
+    let monster = sea_monster();
+    let monsters = jigsaw.find_pattern(&monster, Orientation::all());
+    hash_tile_count - monster.covered_cells(&monsters).len()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let tiles: Vec<Tile> = parse_newline_sep::<String>(input)?
         .map(|s| {
             s.split("\n")
@@ -369,18 +287,10 @@ pub fn part1(input: &Path) -> Result<(), Error> {
         .map(|lines| parse_tile(lines))
         .collect();
 
-    println!(
-        "The answer to part one is {}",
-        find_corner_pieces(&tiles)
-            .iter()
-            .map(|t| t.id)
-            .product::<usize>()
-    );
-
-    Ok(())
+    Ok(find_corner_pieces(&tiles).iter().map(|t| t.id).product())
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let tiles: Vec<Tile> = parse_newline_sep::<String>(input)?
         .map(|s| {
             s.split("\n")
@@ -391,9 +301,7 @@ pub fn part2(input: &Path) -> Result<(), Error> {
         .map(|lines| parse_tile(lines))
         .collect();
 
-    println!("The answer to part two is {}", water_roughness(tiles));
-
-    Ok(())
+    Ok(water_roughness(tiles))
 }
 
 #[derive(Debug, Error)]
@@ -575,7 +483,7 @@ fn test_assemble_jigsaw() {
 
     let expectable_orientations = Tile {
         id: 0,
-        contents: expected,
+        contents: parse_map(&expected),
     }
     .orientations();
 
@@ -588,15 +496,8 @@ fn test_assemble_jigsaw() {
 
 #[test]
 fn test_rotate() {
-    let example = Tile {
-        id: 0,
-        contents: vec!["ABC".to_string(), "DEF".to_string(), "GHI".to_string()],
-    };
-
-    let expected = Tile {
-        id: 0,
-        contents: vec!["GDA".to_string(), "HEB".to_string(), "IFC".to_string()],
-    };
+    let example = parse_map(&["ABC".to_string(), "DEF".to_string(), "GHI".to_string()]);
+    let expected = parse_map(&["GDA".to_string(), "HEB".to_string(), "IFC".to_string()]);
 
     assert_eq!(example.rotate(1), expected);
     assert_eq!(example.rotate(4), example);
@@ -604,23 +505,25 @@ fn test_rotate() {
 
 #[test]
 fn test_flip() {
-    let example = Tile {
-        id: 0,
-        contents: vec!["ABC".to_string(), "DEF".to_string(), "GHI".to_string()],
-    };
-    let expected = Tile {
-        id: 0,
-        contents: vec!["CBA".to_string(), "FED".to_string(), "IHG".to_string()],
-    };
-    assert_eq!(example.flip(), expected);
-    assert_eq!(example.flip().flip(), example);
+    let example = parse_map(&["ABC".to_string(), "DEF".to_string(), "GHI".to_string()]);
+    let expected = parse_map(&["CBA".to_string(), "FED".to_string(), "IHG".to_string()]);
+    assert_eq!(example.flip_horizontal(), expected);
+    assert_eq!(example.flip_horizontal().flip_horizontal(), example);
+}
+
+#[cfg(test)]
+fn has_any_sea_monsters(tile: &Tile) -> bool {
+    !tile
+        .contents
+        .find_pattern(&sea_monster(), Some(Orientation::default()))
+        .is_empty()
 }
 
 #[test]
 fn test_has_any_sea_monsters() {
     let monstery_tile = Tile {
         id: 0,
-        contents: vec![
+        contents: parse_map(&[
             ".####...#####..#...###..".to_string(),
             "#####..#..#.#.####..#.#.".to_string(),
             ".#.#...#.###...#.##.##..".to_string(),
@@ -645,9 +548,12 @@ fn test_has_any_sea_monsters() {
             "#....##..#.#########..##".to_string(),
             "#...#.....#..##...###.##".to_string(),
             "#..###....##.#...##.##.#".to_string(),
-        ],
+        ]),
     };
 
     assert!(has_any_sea_monsters(&monstery_tile));
-    assert!(!has_any_sea_monsters(&monstery_tile.rotate(1)));
+    assert!(!has_any_sea_monsters(&Tile {
+        id: 0,
+        contents: monstery_tile.contents.rotate(1)
+    }));
 }
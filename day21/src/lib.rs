@@ -117,22 +117,14 @@ fn dangerous_ingredient_list(foods: Vec<Food>) -> String {
         .join(",")
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let foods: Vec<Food> = parse::<Food>(input)?.collect();
-    println!(
-        "The answer to part one is {}",
-        non_allergen_appearances(foods)
-    );
-    Ok(())
+    Ok(non_allergen_appearances(foods))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<String, Error> {
     let foods: Vec<Food> = parse::<Food>(input)?.collect();
-    println!(
-        "The answer to part one is {}",
-        dangerous_ingredient_list(foods)
-    );
-    Ok(())
+    Ok(dangerous_ingredient_list(foods))
 }
 
 #[derive(Debug, Error)]
@@ -83,34 +83,27 @@ fn recursive_combat(player_1: Vec<usize>, player_2: Vec<usize>) -> usize {
     player1_wins_subgame(player_1, player_2).1
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let mut decks = parse_newline_sep::<String>(input)?.map(|s| {
         s.split("\n")
             .filter_map(|l| l.parse::<usize>().ok())
             .collect::<Vec<usize>>()
     });
 
-    println!(
-        "The answer to part one is {:?}",
-        combat(decks.next().unwrap(), decks.next().unwrap())
-    );
-
-    Ok(())
+    Ok(combat(decks.next().unwrap(), decks.next().unwrap()))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let mut decks = parse_newline_sep::<String>(input)?.map(|s| {
         s.split("\n")
             .filter_map(|l| l.parse::<usize>().ok())
             .collect::<Vec<usize>>()
     });
 
-    println!(
-        "The answer to part two is {:?}",
-        recursive_combat(decks.next().unwrap(), decks.next().unwrap())
-    );
-
-    Ok(())
+    Ok(recursive_combat(
+        decks.next().unwrap(),
+        decks.next().unwrap(),
+    ))
 }
 
 #[derive(Debug, Error)]
@@ -4,9 +4,13 @@ use std::collections::HashMap;
 use std::path::Path;
 use thiserror::Error;
 
+#[tracing::instrument(skip(ordering))]
 fn simulate_moves(ordering: &mut HashMap<usize, usize>, first: usize, n: usize, max: usize) {
     let mut current_cup = first;
-    for _ in 0..n {
+    for move_ in 0..n {
+        if move_ % 1_000_000 == 0 {
+            tracing::debug!(move_, current_cup);
+        }
         let cup_0 = ordering[&current_cup];
         let cup_1 = ordering[&cup_0];
         let cup_2 = ordering[&cup_1];
@@ -96,26 +100,14 @@ fn simulate_ten_million_moves(initial_order: String) -> usize {
     ordering[&1] * ordering[&ordering[&1]]
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<String, Error> {
     let initial_order = parse::<String>(input)?.next().unwrap();
-
-    println!(
-        "The answer to part one is {:?}",
-        simulate_hundred_moves(initial_order)
-    );
-
-    Ok(())
+    Ok(simulate_hundred_moves(initial_order))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let initial_order = parse::<String>(input)?.next().unwrap();
-
-    println!(
-        "The answer to part two is {:?}",
-        simulate_ten_million_moves(initial_order)
-    );
-
-    Ok(())
+    Ok(simulate_ten_million_moves(initial_order))
 }
 
 #[derive(Debug, Error)]
@@ -1,48 +1,19 @@
+use aoc2020::automaton::Automaton;
+use aoc2020::geometry::{HexPath, HexPoint};
 use aoc2020::parse;
+use aoc2020::render::{self, Frame, Rgb};
 
-use lazy_static::lazy_static;
-use regex::Regex;
 use std::collections::HashSet;
 use std::path::Path;
 use thiserror::Error;
 
-lazy_static! {
-    static ref DIRECTIONS: Regex = Regex::new("(e|se|sw|w|nw|ne)").unwrap();
-}
-
-type Tile = (isize, isize);
+type Tile = HexPoint;
 
 fn parse_tile(directions: String) -> Tile {
-    let mut north = 0;
-    let mut east = 0;
-
-    for dir in DIRECTIONS.find_iter(&directions) {
-        match dir.as_str() {
-            "e" => east += 2,
-            "se" => {
-                east += 1;
-                north -= 1;
-            }
-            "sw" => {
-                east -= 1;
-                north -= 1;
-            }
-            "w" => {
-                east -= 2;
-            }
-            "nw" => {
-                east -= 1;
-                north += 1;
-            }
-            "ne" => {
-                east += 1;
-                north += 1;
-            }
-            _ => panic!("Invalid direction"),
-        }
-    }
-
-    (north, east)
+    directions
+        .parse::<HexPath>()
+        .expect("Invalid direction")
+        .follow(HexPoint::default())
 }
 
 fn get_flipped_tiles<I>(tiles: I) -> HashSet<Tile>
@@ -60,15 +31,13 @@ where
     flipped
 }
 
-fn neighbours(tile: &Tile) -> Vec<Tile> {
-    vec![
-        (tile.0, tile.1 + 2),
-        (tile.0, tile.1 - 2),
-        (tile.0 + 1, tile.1 + 1),
-        (tile.0 + 1, tile.1 - 1),
-        (tile.0 - 1, tile.1 + 1),
-        (tile.0 - 1, tile.1 - 1),
-    ]
+/// Draw black tiles on white, each two pixels wide so that alternate rows can be offset.
+fn draw_tiles(black: &HashSet<Tile>) -> Frame {
+    let pixels = black.iter().flat_map(|tile| {
+        let left = tile.doubled();
+        vec![(left, Rgb::BLACK), (left + (1, 0), Rgb::BLACK)]
+    });
+    Frame::from_points(pixels, Rgb::WHITE)
 }
 
 fn count_flipped_tiles<I>(tiles: I) -> usize
@@ -78,54 +47,35 @@ where
     get_flipped_tiles(tiles).len()
 }
 
-fn advance_day(set: HashSet<Tile>) -> HashSet<Tile> {
-    let remaining = set
-        .iter()
-        .filter(|t| {
-            let neighbour_count = neighbours(t).iter().filter(|n| set.contains(n)).count();
-            neighbour_count == 1 || neighbour_count == 2
-        })
-        .cloned();
-
-    let new = set
-        .iter()
-        .flat_map(|t| neighbours(t))
-        .filter(|t| !set.contains(t))
-        .filter(|t| neighbours(t).iter().filter(|n| set.contains(n)).count() == 2);
-
-    remaining.into_iter().chain(new).collect()
-}
-
 fn flipped_tiles_after_100_days<I>(tiles: I) -> usize
 where
     I: Iterator<Item = Tile>,
 {
-    (0..100)
-        .fold(get_flipped_tiles(tiles), |set, _| advance_day(set))
+    let automaton = Automaton::new(HexPoint::neighbours, |black: &bool, neighbours: &[bool]| {
+        let black_neighbours = neighbours.iter().filter(|n| **n).count();
+        black_neighbours == 2 || (*black && black_neighbours == 1)
+    });
+    automaton
+        .run_visiting(get_flipped_tiles(tiles), 100, |black| {
+            render::frame(|| draw_tiles(black))
+        })
         .len()
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
+pub fn part1(input: &Path) -> Result<usize, Error> {
     let tiles = parse::<String>(input)?
         .take_while(|s| s != "")
         .map(|s| parse_tile(s));
 
-    println!("The answer to part one is {:?}", count_flipped_tiles(tiles));
-
-    Ok(())
+    Ok(count_flipped_tiles(tiles))
 }
 
-pub fn part2(input: &Path) -> Result<(), Error> {
+pub fn part2(input: &Path) -> Result<usize, Error> {
     let tiles = parse::<String>(input)?
         .take_while(|s| s != "")
         .map(|s| parse_tile(s));
 
-    println!(
-        "The answer to part two is {:?}",
-        flipped_tiles_after_100_days(tiles)
-    );
-
-    Ok(())
+    Ok(flipped_tiles_after_100_days(tiles))
 }
 
 #[derive(Debug, Error)]
@@ -1,50 +1,104 @@
+use aoc2020::math::{pohlig_hellman, pow_mod};
 use aoc2020::parse;
 
 use std::path::Path;
 use thiserror::Error;
 
-const MODULO: usize = 20201227;
+/// Every transformation is modulo this prime.
+pub const MODULO: u64 = 20201227;
+/// The subject number transformed to produce public keys.
+pub const SUBJECT: u64 = 7;
 
-fn transform(key: usize, times: usize) -> usize {
-    (0..times).fold(1, |acc, _| (acc * key) % MODULO)
+/// Transform `subject` with the given loop size.
+pub fn transform(subject: u64, loop_size: u64) -> u64 {
+    pow_mod(subject, loop_size, MODULO)
 }
 
-fn get_key(card_key: usize, door_key: usize) -> usize {
-    let mut subj = 1;
-    let mut card_priv = 0;
-    while subj != card_key {
-        subj = (subj * 7) % MODULO;
-        card_priv += 1;
-    }
+/// The public key of a device with the given secret loop size.
+pub fn public_key(loop_size: u64) -> u64 {
+    transform(SUBJECT, loop_size)
+}
 
-    transform(door_key, card_priv)
+/// The least secret loop size producing `public_key`, if any does.
+///
+/// This is a discrete logarithm; the group order `MODULO - 1` factors as `2 * 3 * 29 * 116099`,
+/// so Pohlig-Hellman finds it quickly.
+pub fn loop_size(public_key: u64) -> Option<u64> {
+    pohlig_hellman(SUBJECT, public_key, MODULO, MODULO - 1)
 }
 
-pub fn part1(input: &Path) -> Result<(), Error> {
-    let mut input = parse::<usize>(input)?.take(2);
+/// The encryption key a device with the given loop size derives from the other's public key.
+pub fn encryption_key(other_public_key: u64, loop_size: u64) -> u64 {
+    transform(other_public_key, loop_size)
+}
 
-    println!(
-        "The answer to part one is {:?}",
-        get_key(input.next().unwrap(), input.next().unwrap())
-    );
+/// Both sides of a handshake between the card and the door.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handshake {
+    pub card_loop_size: u64,
+    pub door_loop_size: u64,
+    pub card_public_key: u64,
+    pub door_public_key: u64,
+    pub encryption_key: u64,
+}
 
-    Ok(())
+impl Handshake {
+    /// Perform a handshake between devices with the given secret loop sizes.
+    pub fn simulate(card_loop_size: u64, door_loop_size: u64) -> Handshake {
+        let card_public_key = public_key(card_loop_size);
+        let door_public_key = public_key(door_loop_size);
+        let encryption_key = encryption_key(door_public_key, card_loop_size);
+        debug_assert_eq!(
+            encryption_key,
+            self::encryption_key(card_public_key, door_loop_size)
+        );
+        Handshake {
+            card_loop_size,
+            door_loop_size,
+            card_public_key,
+            door_public_key,
+            encryption_key,
+        }
+    }
+
+    /// Reconstruct a handshake from the public keys alone, by recovering both loop sizes.
+    pub fn crack(card_public_key: u64, door_public_key: u64) -> Result<Handshake, Error> {
+        let card_loop_size =
+            loop_size(card_public_key).ok_or(Error::NoLoopSize(card_public_key))?;
+        let door_loop_size =
+            loop_size(door_public_key).ok_or(Error::NoLoopSize(door_public_key))?;
+        Ok(Handshake::simulate(card_loop_size, door_loop_size))
+    }
 }
 
-pub fn part2(_input: &Path) -> Result<(), Error> {
-    unimplemented!()
+fn get_key(card_key: u64, door_key: u64) -> Result<u64, Error> {
+    Ok(Handshake::crack(card_key, door_key)?.encryption_key)
+}
+
+pub fn part1(input: &Path) -> Result<u64, Error> {
+    let mut input = parse::<u64>(input)?.take(2);
+    get_key(input.next().unwrap(), input.next().unwrap())
+}
+
+/// Day 25 has only one puzzle.
+pub fn part2(_input: &Path) -> Result<usize, Error> {
+    Err(Error::NoPartTwo)
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    #[error("No loop size produces public key {0}")]
+    NoLoopSize(u64),
+    #[error("Day 25 has no part two")]
+    NoPartTwo,
 }
 
 #[cfg(test)]
 #[test]
 fn test_get_key() {
-    assert_eq!(get_key(17807724, 5764801), 14897079);
+    assert_eq!(get_key(17807724, 5764801).unwrap(), 14897079);
 }
 
 #[test]
@@ -52,3 +106,20 @@ fn test_transform() {
     assert_eq!(transform(17807724, 8), 14897079);
     assert_eq!(transform(5764801, 11), 14897079);
 }
+
+#[test]
+fn test_handshake() {
+    let handshake = Handshake::crack(5764801, 17807724).unwrap();
+    assert_eq!(handshake, Handshake::simulate(8, 11));
+    assert_eq!(handshake.encryption_key, 14897079);
+
+    let handshake = Handshake::simulate(12_345_678, 20_201_000);
+    assert_eq!(
+        Handshake::crack(handshake.card_public_key, handshake.door_public_key).unwrap(),
+        handshake
+    );
+    assert!(matches!(
+        Handshake::crack(0, 5764801),
+        Err(Error::NoLoopSize(0))
+    ));
+}
@@ -0,0 +1,321 @@
+use crate::geometry::{Map, Point, VisibilityGraph};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// How cells are connected: which cells' states determine the next state of a given cell.
+///
+/// Neighbours are visited rather than returned, so that precomputed topologies can hand out
+/// the cells they store without copying them on every step.
+pub trait Topology<C> {
+    fn for_each_neighbour<F: FnMut(&C)>(&self, cell: &C, visit: F);
+}
+
+/// Any function from a cell to its neighbours is a topology.
+impl<C, T> Topology<C> for T
+where
+    T: Fn(&C) -> Vec<C>,
+{
+    fn for_each_neighbour<F: FnMut(&C)>(&self, cell: &C, visit: F) {
+        self(cell).iter().for_each(visit)
+    }
+}
+
+/// A precomputed neighbour graph is a topology.
+///
+/// Cells missing from the graph have no neighbours.
+impl<C: Eq + Hash> Topology<C> for HashMap<C, Vec<C>> {
+    fn for_each_neighbour<F: FnMut(&C)>(&self, cell: &C, visit: F) {
+        self.get(cell).into_iter().flatten().for_each(visit)
+    }
+}
+
+/// Each cell's neighbours are the cells it can see.
+impl Topology<Point> for VisibilityGraph {
+    fn for_each_neighbour<F: FnMut(&Point)>(&self, cell: &Point, visit: F) {
+        self.visible(*cell).iter().for_each(visit)
+    }
+}
+
+/// The state of every cell of an automaton.
+pub trait Store<C>: Clone + PartialEq {
+    type State;
+
+    fn state(&self, cell: &C) -> Self::State;
+
+    /// Every cell whose state might change in the next step.
+    fn candidates<T: Topology<C>>(&self, topology: &T) -> Vec<C>;
+
+    /// A store like this one, but with the given cells updated.
+    fn updated<I>(&self, updates: I) -> Self
+    where
+        I: IntoIterator<Item = (C, Self::State)>;
+}
+
+/// A dense store: every cell of the map is evaluated on every step.
+impl<S: Clone + PartialEq> Store<Point> for Map<S> {
+    type State = S;
+
+    fn state(&self, cell: &Point) -> S {
+        self[*cell].clone()
+    }
+
+    fn candidates<T: Topology<Point>>(&self, _topology: &T) -> Vec<Point> {
+        let mut cells = Vec::new();
+        self.for_each_point(|_, point| cells.push(point));
+        cells
+    }
+
+    fn updated<I>(&self, updates: I) -> Self
+    where
+        I: IntoIterator<Item = (Point, S)>,
+    {
+        let mut map = self.clone();
+        for (point, state) in updates {
+            map[point] = state;
+        }
+        map
+    }
+}
+
+/// A sparse store of live cells in an unbounded space.
+///
+/// Only live cells and their neighbours are evaluated, so a dead cell with no live neighbours
+/// must stay dead.
+impl<C: Clone + Eq + Hash> Store<C> for HashSet<C> {
+    type State = bool;
+
+    fn state(&self, cell: &C) -> bool {
+        self.contains(cell)
+    }
+
+    fn candidates<T: Topology<C>>(&self, topology: &T) -> Vec<C> {
+        let mut cells: HashSet<C> = self.clone();
+        for cell in self {
+            topology.for_each_neighbour(cell, |neighbour| {
+                cells.insert(neighbour.clone());
+            });
+        }
+        cells.into_iter().collect()
+    }
+
+    fn updated<I>(&self, updates: I) -> Self
+    where
+        I: IntoIterator<Item = (C, bool)>,
+    {
+        let mut live = self.clone();
+        for (cell, alive) in updates {
+            if alive {
+                live.insert(cell);
+            } else {
+                live.remove(&cell);
+            }
+        }
+        live
+    }
+}
+
+/// A repeating sequence of states, as found by [`Automaton::find_cycle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    /// The number of steps before the first repeated state.
+    pub start: usize,
+    /// The number of steps after which states repeat; 1 if the automaton is stable.
+    pub period: usize,
+    /// The first repeated state.
+    pub state: S,
+}
+
+/// A cellular automaton: a topology, and a rule computing a cell's next state from its current
+/// state and those of its neighbours.
+///
+/// Rules must be deterministic.
+pub struct Automaton<T, R> {
+    topology: T,
+    rule: R,
+}
+
+impl<T, R> Automaton<T, R> {
+    pub fn new(topology: T, rule: R) -> Automaton<T, R> {
+        Automaton { topology, rule }
+    }
+
+    /// Compute the next state of every cell.
+    pub fn step<C, S>(&self, store: &S) -> S
+    where
+        T: Topology<C>,
+        S: Store<C>,
+        R: Fn(&S::State, &[S::State]) -> S::State,
+    {
+        let mut neighbours = Vec::new();
+        let updates = store
+            .candidates(&self.topology)
+            .into_iter()
+            .map(|cell| {
+                neighbours.clear();
+                self.topology
+                    .for_each_neighbour(&cell, |neighbour| neighbours.push(store.state(neighbour)));
+                let next = (self.rule)(&store.state(&cell), &neighbours);
+                (cell, next)
+            })
+            .collect::<Vec<_>>();
+        store.updated(updates)
+    }
+
+    /// Step `n` times.
+    pub fn run<C, S>(&self, store: S, n: usize) -> S
+    where
+        T: Topology<C>,
+        S: Store<C>,
+        R: Fn(&S::State, &[S::State]) -> S::State,
+    {
+        self.run_visiting(store, n, |_| {})
+    }
+
+    /// Step `n` times, calling `visit` with every state, starting with `store`.
+    pub fn run_visiting<C, S, F>(&self, store: S, n: usize, mut visit: F) -> S
+    where
+        T: Topology<C>,
+        S: Store<C>,
+        R: Fn(&S::State, &[S::State]) -> S::State,
+        F: FnMut(&S),
+    {
+        visit(&store);
+        (0..n).fold(store, |store, _| {
+            let next = self.step(&store);
+            visit(&next);
+            next
+        })
+    }
+
+    /// Step until a step changes nothing, returning the stable state and the number of steps
+    /// which changed something.
+    ///
+    /// Never returns if the automaton doesn't stabilize; see [`find_cycle`](Self::find_cycle).
+    pub fn run_until_stable<C, S>(&self, store: S) -> (S, usize)
+    where
+        T: Topology<C>,
+        S: Store<C>,
+        R: Fn(&S::State, &[S::State]) -> S::State,
+    {
+        self.run_until_stable_visiting(store, |_| {})
+    }
+
+    /// Like [`run_until_stable`](Self::run_until_stable), calling `visit` with every distinct
+    /// state, starting with `store`.
+    pub fn run_until_stable_visiting<C, S, F>(&self, store: S, mut visit: F) -> (S, usize)
+    where
+        T: Topology<C>,
+        S: Store<C>,
+        R: Fn(&S::State, &[S::State]) -> S::State,
+        F: FnMut(&S),
+    {
+        let mut current = store;
+        let mut steps = 0;
+        visit(&current);
+        loop {
+            let next = self.step(&current);
+            if next == current {
+                return (current, steps);
+            }
+            visit(&next);
+            current = next;
+            steps += 1;
+        }
+    }
+
+    /// Find where the sequence of states starting at `store` begins to repeat.
+    ///
+    /// This uses Brent's algorithm, so it only ever holds a couple of states at once, at the
+    /// cost of stepping some states more than once.
+    pub fn find_cycle<C, S>(&self, store: S) -> Cycle<S>
+    where
+        T: Topology<C>,
+        S: Store<C>,
+        R: Fn(&S::State, &[S::State]) -> S::State,
+    {
+        // find the period by racing a hare ahead of a tortoise which teleports to it at
+        // successive powers of two
+        let mut power = 1;
+        let mut period = 1;
+        let mut tortoise = store.clone();
+        let mut hare = self.step(&store);
+        while tortoise != hare {
+            if power == period {
+                tortoise = hare.clone();
+                power *= 2;
+                period = 0;
+            }
+            hare = self.step(&hare);
+            period += 1;
+        }
+
+        // then the start, by walking two states a period apart until they meet
+        let mut tortoise = store.clone();
+        let mut hare = self.run(store, period);
+        let mut start = 0;
+        while tortoise != hare {
+            tortoise = self.step(&tortoise);
+            hare = self.step(&hare);
+            start += 1;
+        }
+
+        Cycle {
+            start,
+            period,
+            state: tortoise,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Life = Automaton<fn(&Point) -> Vec<Point>, fn(&bool, &[bool]) -> bool>;
+
+    fn life() -> Life {
+        fn neighbours(point: &Point) -> Vec<Point> {
+            let mut neighbours = Vec::new();
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    if dx != 0 || dy != 0 {
+                        neighbours.push(*point + (dx, dy));
+                    }
+                }
+            }
+            neighbours
+        }
+        fn rule(alive: &bool, neighbours: &[bool]) -> bool {
+            let live = neighbours.iter().filter(|n| **n).count();
+            live == 3 || (*alive && live == 2)
+        }
+        Automaton::new(neighbours, rule)
+    }
+
+    fn cells(points: &[(i32, i32)]) -> HashSet<Point> {
+        points.iter().map(|(x, y)| Point::new(*x, *y)).collect()
+    }
+
+    #[test]
+    fn test_find_cycle_blinker() {
+        let blinker = cells(&[(0, 0), (1, 0), (2, 0)]);
+        let cycle = life().find_cycle(blinker.clone());
+        assert_eq!(cycle.start, 0);
+        assert_eq!(cycle.period, 2);
+        assert_eq!(cycle.state, blinker);
+    }
+
+    #[test]
+    fn test_find_cycle_settles() {
+        // an L-tromino becomes a block after one step
+        let tromino = cells(&[(0, 0), (1, 0), (0, 1)]);
+        let cycle = life().find_cycle(tromino);
+        assert_eq!(cycle.start, 1);
+        assert_eq!(cycle.period, 1);
+        assert_eq!(cycle.state, cells(&[(0, 0), (1, 0), (0, 1), (1, 1)]));
+        assert_eq!(
+            life().run_until_stable(cycle.state.clone()),
+            (cycle.state, 0)
+        );
+    }
+}
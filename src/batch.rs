@@ -0,0 +1,315 @@
+use crate::runner::{Format, Record};
+use crate::workspace::{self, Invocation};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Options for running many days at once.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// days to run; every initialized day if empty
+    pub days: Vec<u8>,
+    pub part1: bool,
+    pub part2: bool,
+    /// number of worker threads; the available parallelism if unset
+    pub jobs: Option<usize>,
+    pub release: bool,
+    pub format: Format,
+}
+
+/// A single part of a single day, run in its own process.
+#[derive(Debug, Clone)]
+struct Job {
+    day: u8,
+    part: u8,
+    binary: PathBuf,
+}
+
+/// What happened when a job ran.
+#[derive(Debug)]
+struct Outcome {
+    result: Result<Record, Error>,
+    started: Instant,
+    finished: Instant,
+}
+
+impl Job {
+    fn run(&self) -> Outcome {
+        let started = Instant::now();
+        let result = self.record();
+        Outcome {
+            result,
+            started,
+            finished: Instant::now(),
+        }
+    }
+
+    fn record(&self) -> Result<Record, Error> {
+        let invocation = Invocation {
+            input: None,
+            part1: self.part == 1,
+            part2: self.part == 2,
+        };
+        let output = Command::new(&self.binary)
+            .args(invocation.args())
+            .args(["--format", "json"])
+            // keep failure reports down to their summary
+            .env("RUST_BACKTRACE", "0")
+            .env("RUST_LIB_BACKTRACE", "0")
+            .output()
+            .map_err(workspace::Error::from)?;
+        if !output.status.success() {
+            return Err(Error::Failed(
+                self.day,
+                self.part,
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+
+        self.parse_record(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// The record in a day binary's json output.
+    fn parse_record(&self, stdout: &str) -> Result<Record, Error> {
+        // solvers may print progress of their own; the record is always the last line
+        let line = stdout
+            .lines()
+            .next_back()
+            .ok_or(Error::NoRecord(self.day, self.part))?;
+        serde_json::from_str(line).map_err(|_| Error::NoRecord(self.day, self.part))
+    }
+}
+
+/// Buffers results arriving in any order, releasing each as soon as all its predecessors have
+/// been released.
+struct Reorder<T> {
+    pending: Vec<Option<T>>,
+    next: usize,
+}
+
+impl<T> Reorder<T> {
+    fn new(len: usize) -> Reorder<T> {
+        Reorder {
+            pending: (0..len).map(|_| None).collect(),
+            next: 0,
+        }
+    }
+
+    /// Accept the result with index `idx`, returning every result now ready, in order.
+    fn accept(&mut self, idx: usize, result: T) -> Vec<(usize, T)> {
+        self.pending[idx] = Some(result);
+        let mut ready = Vec::new();
+        while let Some(result) = self.pending.get_mut(self.next).and_then(Option::take) {
+            ready.push((self.next, result));
+            self.next += 1;
+        }
+        ready
+    }
+}
+
+/// Run the selected parts of many days across a pool of worker threads.
+///
+/// Each part runs in its own process, so slow days don't hold up fast ones. Results are
+/// emitted in day and part order as soon as every preceding result is available, regardless
+/// of the order in which they complete.
+///
+/// After each day, its wall time is reported, followed by the total wall time at the end.
+/// For machine-readable formats these timings go to stderr, leaving stdout pure records.
+///
+/// Returns the number of parts which failed.
+pub fn run(options: &Options) -> Result<usize, Error> {
+    let root = workspace::root()?;
+    let days = if options.days.is_empty() {
+        workspace::days(&root)?
+    } else {
+        options.days.clone()
+    };
+    let binaries = workspace::build_days(&root, &days, options.release)?;
+    let start = Instant::now();
+    let jobs: Vec<Job> = days
+        .iter()
+        .zip(binaries)
+        .flat_map(|(&day, binary)| {
+            let parts = [(1, options.part1), (2, options.part2)];
+            parts
+                .iter()
+                .filter(|&&(part, enabled)| enabled && (part == 1 || workspace::has_part2(day)))
+                .map(|&(part, _)| Job {
+                    day,
+                    part,
+                    binary: binary.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    let workers = options
+        .jobs
+        .or_else(|| std::thread::available_parallelism().ok().map(Into::into))
+        .unwrap_or(1)
+        .clamp(1, jobs.len().max(1));
+
+    let mut emitter = Emitter::new(options.format);
+    let next_job = AtomicUsize::new(0);
+    let (tx, rx) = channel();
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            let tx = tx.clone();
+            let jobs = &jobs;
+            let next_job = &next_job;
+            scope.spawn(move || loop {
+                let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                let job = match jobs.get(idx) {
+                    Some(job) => job,
+                    None => break,
+                };
+                if tx.send((idx, job.run())).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut reorder = Reorder::new(jobs.len());
+        for (idx, outcome) in rx {
+            for (idx, outcome) in reorder.accept(idx, outcome) {
+                let job = &jobs[idx];
+                let day_complete = jobs.get(idx + 1).map(|next| next.day) != Some(job.day);
+                emitter.emit(job, outcome, day_complete);
+            }
+        }
+    });
+
+    emitter.total(start.elapsed());
+    Ok(emitter.failures)
+}
+
+/// Writes outcomes in order, tracking per-day wall time.
+struct Emitter {
+    format: Format,
+    failures: usize,
+    /// (earliest start, latest finish) of the current day's jobs
+    day_span: Option<(Instant, Instant)>,
+}
+
+impl Emitter {
+    fn new(format: Format) -> Emitter {
+        if format == Format::Tsv {
+            println!("{}", Record::TSV_HEADER);
+        }
+        Emitter {
+            format,
+            failures: 0,
+            day_span: None,
+        }
+    }
+
+    fn emit(&mut self, job: &Job, outcome: Outcome, day_complete: bool) {
+        match outcome.result {
+            Ok(record) => match self.format {
+                Format::Human => println!("day {:02}: {}", record.day, record),
+                Format::Json => println!("{}", record.to_json()),
+                Format::Tsv => println!("{}", record.to_tsv()),
+            },
+            Err(err) => {
+                self.failures += 1;
+                eprintln!("{}", err);
+            }
+        }
+
+        let (started, finished) = self.day_span.unwrap_or((outcome.started, outcome.finished));
+        self.day_span = Some((started.min(outcome.started), finished.max(outcome.finished)));
+
+        if day_complete {
+            if let Some((started, finished)) = self.day_span.take() {
+                self.timing(&format!("day {:02}", job.day), finished - started);
+            }
+        }
+    }
+
+    fn total(&self, elapsed: Duration) {
+        self.timing("total", elapsed);
+    }
+
+    fn timing(&self, label: &str, elapsed: Duration) {
+        let line = format!("{} wall time: {:.3}s", label, elapsed.as_secs_f64());
+        match self.format {
+            Format::Human => println!("{}", line),
+            Format::Json | Format::Tsv => eprintln!("{}", line),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Workspace(#[from] workspace::Error),
+    #[error("day {0:02} part {1} failed:\n{2}")]
+    Failed(u8, u8, String),
+    #[error("day {0:02} part {1} produced no record")]
+    NoRecord(u8, u8),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reorder() {
+        let mut reorder = Reorder::new(5);
+        assert_eq!(reorder.accept(2, 'c'), vec![]);
+        assert_eq!(reorder.accept(1, 'b'), vec![]);
+        assert_eq!(reorder.accept(0, 'a'), vec![(0, 'a'), (1, 'b'), (2, 'c')]);
+        assert_eq!(reorder.accept(4, 'e'), vec![]);
+        assert_eq!(reorder.accept(3, 'd'), vec![(3, 'd'), (4, 'e')]);
+    }
+
+    #[test]
+    fn test_reorder_threads() {
+        // workers finishing in reverse order must still be released in job order
+        let (tx, rx) = channel();
+        std::thread::scope(|scope| {
+            for idx in 0..8 {
+                let tx = tx.clone();
+                scope.spawn(move || {
+                    std::thread::sleep(Duration::from_millis(10 * (8 - idx as u64)));
+                    tx.send((idx, idx * 10)).unwrap();
+                });
+            }
+        });
+        drop(tx);
+
+        let mut reorder = Reorder::new(8);
+        let released: Vec<(usize, usize)> = rx
+            .into_iter()
+            .flat_map(|(idx, result)| reorder.accept(idx, result))
+            .collect();
+        assert_eq!(
+            released,
+            (0..8).map(|idx| (idx, idx * 10)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let job = Job {
+            day: 7,
+            part: 2,
+            binary: PathBuf::from("day07"),
+        };
+        let record = r#"{"year":2020,"day":7,"part":2,"answer":"126","parse_time_us":15,"solve_time_us":1234,"input_checksum":"af63dc4c8601ec8c"}"#;
+        let parsed = job
+            .parse_record(&format!("progress...\n{}\n", record))
+            .unwrap();
+        assert_eq!(parsed.answer, "126");
+        assert!(matches!(job.parse_record(""), Err(Error::NoRecord(7, 2))));
+        assert!(matches!(
+            job.parse_record("The answer to part two is 126\n"),
+            Err(Error::NoRecord(7, 2))
+        ));
+    }
+}
@@ -10,7 +10,7 @@ use crate::config::Config;
 const EXPECT_PACKAGE: &str = env!("CARGO_PKG_NAME");
 
 /// ensure we're in the correct directory by verifying the package name in `Cargo.toml`
-fn ensure_correct_dir(current_dir: &Path) -> Result<(PathBuf, Document), Error> {
+pub(crate) fn ensure_correct_dir(current_dir: &Path) -> Result<(PathBuf, Document), Error> {
     // parse the local Cargo.toml to discover if we're in the right place
     let cargo_toml_path = current_dir.join("Cargo.toml");
     if !cargo_toml_path.exists() {
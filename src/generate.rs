@@ -0,0 +1,1473 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+use thiserror::Error;
+
+/// A small, fast, deterministic pseudo-random number generator (SplitMix64).
+///
+/// This is implemented here rather than taken from a crate so that a given seed produces the
+/// same input forever, independent of upstream changes to any generator's stream.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniformly distributed value within `range`.
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range");
+        let width = (end - start) as u64 + 1;
+        start + (self.next_u64() % width) as usize
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..=items.len() - 1)]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+/// Generate an input for `day`.
+///
+/// The same `day`, `seed` and `scale` always produce the same input. `scale` multiplies the
+/// size of the input relative to a typical real one. Some puzzles have no room to grow (boarding
+/// passes must fit on the plane, bus ids must keep part 2 within 64 bits, jigsaw edges must stay
+/// unique...); those generators ignore `scale` or cap the size they reach.
+pub fn generate(day: u8, seed: u64, scale: usize) -> Result<String, Error> {
+    if scale == 0 {
+        return Err(Error::ZeroScale);
+    }
+    let generator = match day {
+        1 => day01,
+        2 => day02,
+        3 => day03,
+        4 => day04,
+        5 => day05,
+        6 => day06,
+        7 => day07,
+        8 => day08,
+        9 => day09,
+        10 => day10,
+        11 => day11,
+        12 => day12,
+        13 => day13,
+        14 => day14,
+        15 => day15,
+        16 => day16,
+        17 => day17,
+        18 => day18,
+        19 => day19,
+        20 => day20,
+        21 => day21,
+        22 => day22,
+        23 => day23,
+        24 => day24,
+        25 => day25,
+        _ => return Err(Error::NoGenerator(day)),
+    };
+    // mix the day in, so that one seed doesn't produce correlated inputs across days
+    let mut rng = Rng::new(seed ^ (u64::from(day) << 56));
+    Ok(generator(&mut rng, scale))
+}
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+/// One item per line, with a trailing newline.
+fn lines<I>(items: I) -> String
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut out = String::new();
+    for item in items {
+        out.push_str(item.as_ref());
+        out.push('\n');
+    }
+    out
+}
+
+fn grid(cells: &[Vec<bool>]) -> Vec<String> {
+    cells
+        .iter()
+        .map(|row| row.iter().map(|&c| if c { '#' } else { '.' }).collect())
+        .collect()
+}
+
+fn word(rng: &mut Rng, alphabet: &[u8], len: RangeInclusive<usize>) -> String {
+    let len = rng.range(len);
+    (0..len).map(|_| *rng.choose(alphabet) as char).collect()
+}
+
+/// Expense report with exactly one pair and one triple summing to 2020.
+fn day01(rng: &mut Rng, scale: usize) -> String {
+    const TARGET: usize = 2020;
+
+    let specials = loop {
+        let a = rng.range(600..=1000);
+        let b = rng.range(520..=1000);
+        let c = rng.range(520..=1000);
+        let d = match TARGET.checked_sub(b + c) {
+            Some(d) if (520..=1000).contains(&d) => d,
+            _ => continue,
+        };
+        let specials = vec![a, TARGET - a, b, c, d];
+        if specials.iter().collect::<HashSet<_>>().len() != specials.len() {
+            continue;
+        }
+        let n = specials.len();
+        let mut pairs = 0;
+        let mut triples = 0;
+        for i in 0..n {
+            for j in i + 1..n {
+                pairs += (specials[i] + specials[j] == TARGET) as usize;
+                for k in j + 1..n {
+                    triples += (specials[i] + specials[j] + specials[k] == TARGET) as usize;
+                }
+            }
+        }
+        if pairs == 1 && triples == 1 {
+            break specials;
+        }
+    };
+
+    // fillers are all over 1010, so no two sum to 2020 and none can be part of a triple;
+    // excluding complements of the specials leaves no other pairs
+    let mut fillers: Vec<usize> = (TARGET / 2 + 1..TARGET)
+        .filter(|f| !specials.iter().any(|s| *s == *f || s + f == TARGET))
+        .collect();
+    rng.shuffle(&mut fillers);
+    fillers.truncate((200 * scale).saturating_sub(specials.len()));
+
+    let mut entries = specials;
+    entries.extend(fillers);
+    rng.shuffle(&mut entries);
+    lines(entries.iter().map(ToString::to_string))
+}
+
+/// Password policies and passwords.
+fn day02(rng: &mut Rng, scale: usize) -> String {
+    lines((0..1000 * scale).map(|_| {
+        let lo = rng.range(1..=10);
+        let hi = rng.range(lo + 1..=lo + 10);
+        let letter = *rng.choose(LETTERS) as char;
+        let len = rng.range(hi..=hi + 6);
+        let password: String = (0..len)
+            .map(|_| {
+                if rng.chance(0.35) {
+                    letter
+                } else {
+                    *rng.choose(LETTERS) as char
+                }
+            })
+            .collect();
+        format!("{}-{} {}: {}", lo, hi, letter, password)
+    }))
+}
+
+/// A toboggan slope, open at the top left.
+fn day03(rng: &mut Rng, scale: usize) -> String {
+    let mut cells: Vec<Vec<bool>> = (0..323 * scale)
+        .map(|_| (0..31).map(|_| rng.chance(0.22)).collect())
+        .collect();
+    cells[0][0] = false;
+    lines(grid(&cells))
+}
+
+/// Passport batch, with a mix of valid, incomplete and invalid passports.
+fn day04(rng: &mut Rng, scale: usize) -> String {
+    const EYE_COLOURS: &[&str] = &["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+    let passports: Vec<String> = (0..290 * scale)
+        .map(|_| {
+            let hgt = if rng.chance(0.5) {
+                format!("{}cm", rng.range(150..=193))
+            } else {
+                format!("{}in", rng.range(59..=76))
+            };
+            let mut fields = vec![
+                ("byr", rng.range(1920..=2002).to_string()),
+                ("iyr", rng.range(2010..=2020).to_string()),
+                ("eyr", rng.range(2020..=2030).to_string()),
+                ("hgt", hgt),
+                ("hcl", format!("#{:06x}", rng.range(0..=0xff_ffff))),
+                ("ecl", rng.choose(EYE_COLOURS).to_string()),
+                ("pid", format!("{:09}", rng.range(0..=999_999_999))),
+            ];
+            if rng.chance(0.3) {
+                let i = rng.range(0..=fields.len() - 1);
+                fields[i].1 = invalid_passport_value(rng, fields[i].0);
+            }
+            if rng.chance(0.2) {
+                fields.remove(rng.range(0..=fields.len() - 1));
+            }
+            if rng.chance(0.6) {
+                fields.push(("cid", rng.range(60..=350).to_string()));
+            }
+            rng.shuffle(&mut fields);
+
+            let mut passport = String::new();
+            for (i, (key, value)) in fields.iter().enumerate() {
+                if i > 0 {
+                    passport.push(if rng.chance(0.3) { '\n' } else { ' ' });
+                }
+                passport.push_str(&format!("{}:{}", key, value));
+            }
+            passport
+        })
+        .collect();
+    passports.join("\n\n") + "\n"
+}
+
+fn invalid_passport_value(rng: &mut Rng, field: &str) -> String {
+    match field {
+        "byr" => rng.range(2003..=2010).to_string(),
+        "iyr" => rng.range(2000..=2009).to_string(),
+        "eyr" => rng.range(2031..=2040).to_string(),
+        "hgt" => match rng.range(0..=2) {
+            0 => format!("{}cm", rng.range(194..=210)),
+            1 => format!("{}in", rng.range(77..=90)),
+            _ => rng.range(150..=193).to_string(),
+        },
+        "hcl" => format!("#{:05x}z", rng.range(0..=0xf_ffff)),
+        "ecl" => rng.choose(&["xry", "gmt", "zzz", "lzr"]).to_string(),
+        _ => format!("{:010}", rng.range(0..=9_999_999_999)),
+    }
+}
+
+/// Boarding passes for a contiguous block of seats, less one.
+///
+/// Seat ids are at most 1023, so this ignores `scale`.
+fn day05(rng: &mut Rng, _scale: usize) -> String {
+    let first = rng.range(8..=120);
+    let last = rng.range(first + 700..=(first + 880).min(1015));
+    let missing = rng.range(first + 1..=last - 1);
+    let mut ids: Vec<usize> = (first..=last).filter(|id| *id != missing).collect();
+    rng.shuffle(&mut ids);
+
+    lines(ids.iter().map(|id| {
+        let row = (0..7)
+            .rev()
+            .map(|bit| if (id >> 3 >> bit) & 1 == 1 { 'B' } else { 'F' });
+        let column = (0..3)
+            .rev()
+            .map(|bit| if (id >> bit) & 1 == 1 { 'R' } else { 'L' });
+        row.chain(column).collect::<String>()
+    }))
+}
+
+/// Customs declaration groups.
+fn day06(rng: &mut Rng, scale: usize) -> String {
+    let groups: Vec<String> = (0..480 * scale)
+        .map(|_| {
+            let people = rng.range(1..=5);
+            let common: Vec<u8> = LETTERS
+                .iter()
+                .filter(|_| rng.chance(0.25))
+                .copied()
+                .collect();
+            let answers: Vec<String> = (0..people)
+                .map(|_| {
+                    let mut answers: Vec<u8> = LETTERS
+                        .iter()
+                        .filter(|l| common.contains(l) || rng.chance(0.1))
+                        .copied()
+                        .collect();
+                    if answers.is_empty() {
+                        answers.push(*rng.choose(LETTERS));
+                    }
+                    rng.shuffle(&mut answers);
+                    String::from_utf8(answers).expect("letters are ascii")
+                })
+                .collect();
+            answers.join("\n")
+        })
+        .collect();
+    groups.join("\n\n") + "\n"
+}
+
+/// Luggage rules.
+///
+/// Bags are arranged in levels, and only contain bags from the next couple of levels down. This
+/// keeps the rules acyclic and bounds how many bags a shiny gold one can hold, while bags just
+/// above it are biased towards holding it. There are only so many two-word colours, which caps
+/// how far this scales.
+fn day07(rng: &mut Rng, scale: usize) -> String {
+    const ADJECTIVES: &[&str] = &[
+        "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+        "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy", "bold", "calm",
+        "deep", "dusty", "fuzzy", "glossy", "hazy", "matte", "neon", "rusty", "sandy", "smoky",
+    ];
+    const COLOURS: &[&str] = &[
+        "aqua",
+        "beige",
+        "black",
+        "blue",
+        "bronze",
+        "brown",
+        "chartreuse",
+        "coral",
+        "crimson",
+        "cyan",
+        "fuchsia",
+        "gold",
+        "gray",
+        "green",
+        "indigo",
+        "lavender",
+        "lime",
+        "magenta",
+        "maroon",
+        "olive",
+        "orange",
+        "plum",
+        "purple",
+        "red",
+        "salmon",
+        "silver",
+        "tan",
+        "teal",
+        "tomato",
+        "turquoise",
+        "violet",
+        "white",
+        "yellow",
+    ];
+    const LEVELS: usize = 8;
+    const SHINY_GOLD_LEVEL: usize = 3;
+
+    let mut bags: Vec<String> = ADJECTIVES
+        .iter()
+        .flat_map(|adjective| {
+            COLOURS
+                .iter()
+                .map(move |colour| format!("{} {}", adjective, colour))
+        })
+        .filter(|bag| bag != "shiny gold")
+        .collect();
+    rng.shuffle(&mut bags);
+    bags.truncate(594 * scale - 1);
+    let shiny_gold_at = rng.range(
+        bags.len() * SHINY_GOLD_LEVEL / LEVELS..=bags.len() * (SHINY_GOLD_LEVEL + 1) / LEVELS - 1,
+    );
+    bags.insert(shiny_gold_at, "shiny gold".to_string());
+
+    let level_of = |idx: usize| idx * LEVELS / bags.len();
+    let level_start = |level: usize| (level * bags.len()).div_ceil(LEVELS);
+
+    let mut rules: Vec<String> = (0..bags.len())
+        .map(|idx| {
+            let level = level_of(idx);
+            if level == LEVELS - 1 || rng.chance(0.1) {
+                return format!("{} bags contain no other bags.", bags[idx]);
+            }
+            let candidates = level_start(level + 1)..level_start((level + 3).min(LEVELS));
+            let mut contents = HashSet::new();
+            for _ in 0..rng.range(1..=4) {
+                contents.insert(rng.range(candidates.start..=candidates.end - 1));
+            }
+            if candidates.contains(&shiny_gold_at) && rng.chance(0.1) {
+                contents.insert(shiny_gold_at);
+            }
+            let mut contents: Vec<usize> = contents.into_iter().collect();
+            contents.sort_unstable();
+            rng.shuffle(&mut contents);
+            let contents: Vec<String> = contents
+                .iter()
+                .map(|inner| {
+                    let count = rng.range(1..=5);
+                    let plural = if count == 1 { "bag" } else { "bags" };
+                    format!("{} {} {}", count, bags[*inner], plural)
+                })
+                .collect();
+            format!("{} bags contain {}.", bags[idx], contents.join(", "))
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+    lines(rules)
+}
+
+type Instruction = (&'static str, isize);
+
+/// Boot code which loops, but terminates if exactly one `jmp` or `nop` is flipped.
+fn day08(rng: &mut Rng, scale: usize) -> String {
+    loop {
+        if let Some(program) = corrupted_boot_code(rng, 620 * scale) {
+            return lines(program.iter().map(|(op, arg)| format!("{} {:+}", op, arg)));
+        }
+    }
+}
+
+/// Build a program whose execution runs straight off its end, then corrupt one instruction.
+///
+/// Every instruction the fixed program would skip over tends to lead back into the part which
+/// has already run, so taking a wrong turn loops. Returns `None` if the result has any other
+/// fix, or none at all.
+fn corrupted_boot_code(rng: &mut Rng, len: usize) -> Option<Vec<Instruction>> {
+    fn signed(rng: &mut Rng, max: usize) -> isize {
+        let magnitude = rng.range(1..=max) as isize;
+        if rng.chance(0.5) {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    // any jump target in 0..=len keeps the day's solver in bounds, whatever gets flipped
+    fn in_bounds(rng: &mut Rng, pos: usize, len: usize) -> isize {
+        rng.range(0..=len) as isize - pos as isize
+    }
+
+    let mut program: Vec<Instruction> = vec![("nop", 0); len];
+    let mut chain = Vec::new();
+    let mut pos = 0;
+    while pos < len {
+        chain.push(pos);
+        if rng.chance(0.25) {
+            let offset = rng.range(2..=20).min(len - pos);
+            program[pos] = ("jmp", offset as isize);
+            pos += offset;
+        } else {
+            program[pos] = if rng.chance(0.75) {
+                ("acc", signed(rng, 50))
+            } else {
+                ("nop", in_bounds(rng, pos, len))
+            };
+            pos += 1;
+        }
+    }
+
+    for (pos, instruction) in program.iter_mut().enumerate() {
+        if chain.binary_search(&pos).is_ok() {
+            continue;
+        }
+        *instruction = if rng.chance(0.7) {
+            let earlier = chain.partition_point(|p| *p < pos);
+            let target = chain[rng.range(0..=earlier - 1)];
+            ("jmp", target as isize - pos as isize)
+        } else if rng.chance(0.5) {
+            ("acc", signed(rng, 50))
+        } else {
+            ("nop", in_bounds(rng, pos, len))
+        };
+    }
+
+    let corruptible: Vec<usize> = chain
+        .iter()
+        .copied()
+        .filter(|p| match program[*p] {
+            ("jmp", offset) => offset > 1,
+            ("nop", offset) => offset < 0,
+            _ => false,
+        })
+        .collect();
+    if corruptible.is_empty() {
+        return None;
+    }
+    let corrupted = *rng.choose(&corruptible);
+    program[corrupted].0 = if program[corrupted].0 == "jmp" {
+        "nop"
+    } else {
+        "jmp"
+    };
+
+    let fixes = (0..len)
+        .filter(|idx| program[*idx].0 != "acc")
+        .filter(|idx| boot(&program, Some(*idx)))
+        .count();
+    if boot(&program, None) || fixes != 1 {
+        return None;
+    }
+    Some(program)
+}
+
+/// Whether the program terminates, optionally with one `jmp` and `nop` swapped.
+fn boot(program: &[Instruction], flip: Option<usize>) -> bool {
+    let mut visited = vec![false; program.len()];
+    let mut pos = 0;
+    while pos < program.len() {
+        if visited[pos] {
+            return false;
+        }
+        visited[pos] = true;
+        let (op, arg) = program[pos];
+        let jumps = match op {
+            "jmp" => flip != Some(pos),
+            "nop" => flip == Some(pos),
+            _ => false,
+        };
+        pos = if jumps {
+            (pos as isize + arg) as usize
+        } else {
+            pos + 1
+        };
+    }
+    true
+}
+
+/// XMAS-encrypted data, with one number which isn't a sum of two of the previous 25.
+///
+/// Each number is at least the sum of the two smallest of its preamble, so the numbers roughly
+/// double every 25 lines; the length is capped to keep them within 64 bits.
+fn day09(rng: &mut Rng, scale: usize) -> String {
+    let len = (1000 * scale).min(1200);
+    loop {
+        if let Some(numbers) = xmas_numbers(rng, len) {
+            return lines(numbers.iter().map(ToString::to_string));
+        }
+    }
+}
+
+fn xmas_numbers(rng: &mut Rng, len: usize) -> Option<Vec<usize>> {
+    const PREAMBLE: usize = 25;
+
+    let mut numbers: Vec<usize> = (1..=50).collect();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(PREAMBLE);
+    // the puzzle never pairs a number with an equal one, so keep every number distinct
+    let mut seen: HashSet<usize> = numbers.iter().copied().collect();
+    let invalid_at = rng.range(len * 2 / 5..=len * 3 / 5);
+
+    for idx in PREAMBLE..len {
+        let mut window: Vec<usize> = numbers[idx - PREAMBLE..].to_vec();
+        window.sort_unstable();
+
+        let next = if idx == invalid_at {
+            let window: HashSet<usize> = window.into_iter().collect();
+            (0..100).find_map(|_| {
+                let start = rng.range(0..=idx - 18);
+                let sum: usize = numbers[start..start + rng.range(2..=17)].iter().sum();
+                let is_pair_sum = window
+                    .iter()
+                    .any(|x| *x * 2 != sum && window.contains(&sum.wrapping_sub(*x)));
+                Some(sum).filter(|sum| !is_pair_sum && !seen.contains(sum))
+            })
+        } else {
+            // sums of the smallest numbers in the window keep growth as slow as possible
+            (0..20).find_map(|_| {
+                let a = rng.range(0..=7);
+                let b = rng.range(0..=7);
+                let sum = window[a].checked_add(window[b])?;
+                Some(sum).filter(|sum| a != b && !seen.contains(sum) && *sum < 1 << 62)
+            })
+        }?;
+        seen.insert(next);
+        numbers.push(next);
+    }
+    Some(numbers)
+}
+
+/// Joltage adapters, each 1 or 3 jolts above the previous.
+///
+/// Runs of 1-jolt steps multiply the number of arrangements; once that would approach overflow,
+/// only runs which don't multiply it are generated.
+fn day10(rng: &mut Rng, scale: usize) -> String {
+    // arrangements of a run of n 1-jolt steps between 3-jolt steps
+    const ARRANGEMENTS: [usize; 5] = [1, 1, 2, 4, 7];
+    const RUNS: &[usize] = &[0, 1, 2, 3, 4, 4, 4];
+
+    let mut adapters = Vec::new();
+    let mut joltage = 0;
+    let mut arrangements: usize = 1;
+    while adapters.len() < 100 * scale {
+        let mut run = *rng.choose(RUNS);
+        match arrangements.checked_mul(ARRANGEMENTS[run]) {
+            Some(total) if total < 1 << 50 => arrangements = total,
+            _ => run = 1,
+        }
+        for _ in 0..run {
+            joltage += 1;
+            adapters.push(joltage);
+        }
+        joltage += 3;
+        adapters.push(joltage);
+    }
+    rng.shuffle(&mut adapters);
+    lines(adapters.iter().map(ToString::to_string))
+}
+
+/// Waiting area seat layout.
+///
+/// Random layouts don't always settle: some seats keep flipping forever. Those are replaced with
+/// floor until the layout settles under both parts' rules.
+fn day11(rng: &mut Rng, scale: usize) -> String {
+    let mut seats: Vec<Vec<bool>> = (0..91 * scale)
+        .map(|_| (0..95).map(|_| rng.chance(0.83)).collect())
+        .collect();
+    loop {
+        let unsettled: Vec<(usize, usize)> = [false, true]
+            .iter()
+            .flat_map(|line_of_sight| unsettled_seats(&seats, *line_of_sight))
+            .collect();
+        if unsettled.is_empty() {
+            break;
+        }
+        for (y, x) in unsettled {
+            seats[y][x] = false;
+        }
+    }
+    lines(seats.iter().map(|row| {
+        row.iter()
+            .map(|seat| if *seat { 'L' } else { '.' })
+            .collect::<String>()
+    }))
+}
+
+/// Seats which are still changing once the layout has either settled or started repeating.
+///
+/// Without `line_of_sight`, seats consider their immediate neighbours and tolerate three
+/// occupied; with it, they consider the first seat in each direction and tolerate four.
+fn unsettled_seats(seats: &[Vec<bool>], line_of_sight: bool) -> Vec<(usize, usize)> {
+    const DIRECTIONS: [(isize, isize); 8] = [
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+        (0, -1),
+        (0, 1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+    ];
+    let (height, width) = (seats.len() as isize, seats[0].len() as isize);
+    let seat_at = |y: isize, x: isize| {
+        (0..height).contains(&y) && (0..width).contains(&x) && seats[y as usize][x as usize]
+    };
+
+    // for every seat, the seats it can see
+    let mut neighbours = HashMap::new();
+    for y in 0..height {
+        for x in 0..width {
+            if !seat_at(y, x) {
+                continue;
+            }
+            let visible: Vec<(usize, usize)> = DIRECTIONS
+                .iter()
+                .filter_map(|(dy, dx)| {
+                    let (mut ny, mut nx) = (y + dy, x + dx);
+                    while line_of_sight
+                        && !seat_at(ny, nx)
+                        && (0..height).contains(&ny)
+                        && (0..width).contains(&nx)
+                    {
+                        ny += dy;
+                        nx += dx;
+                    }
+                    Some((ny as usize, nx as usize)).filter(|_| seat_at(ny, nx))
+                })
+                .collect();
+            neighbours.insert((y as usize, x as usize), visible);
+        }
+    }
+    let tolerance = if line_of_sight { 4 } else { 3 };
+
+    let mut previous: HashSet<(usize, usize)> = HashSet::new();
+    let mut occupied: HashSet<(usize, usize)> = HashSet::new();
+    for _ in 0..1000 {
+        let next: HashSet<(usize, usize)> = neighbours
+            .iter()
+            .filter(|(seat, visible)| {
+                let around = visible.iter().filter(|n| occupied.contains(n)).count();
+                if occupied.contains(seat) {
+                    around <= tolerance
+                } else {
+                    around == 0
+                }
+            })
+            .map(|(seat, _)| *seat)
+            .collect();
+        if next == occupied {
+            return Vec::new();
+        }
+        if next == previous {
+            break;
+        }
+        previous = std::mem::replace(&mut occupied, next);
+    }
+    let mut unsettled: Vec<(usize, usize)> =
+        occupied.symmetric_difference(&previous).copied().collect();
+    unsettled.sort_unstable();
+    unsettled
+}
+
+/// Navigation instructions.
+fn day12(rng: &mut Rng, scale: usize) -> String {
+    lines((0..780 * scale).map(|_| {
+        let action = *rng.choose(&['N', 'S', 'E', 'W', 'L', 'R', 'F', 'F']);
+        let value = match action {
+            'L' | 'R' => 90 * rng.range(1..=3),
+            'F' => rng.range(1..=100),
+            _ => rng.range(1..=5),
+        };
+        format!("{}{}", action, value)
+    }))
+}
+
+/// Earliest timestamp and bus schedule.
+///
+/// Bus ids are distinct primes, so part 2 always has a solution; they are chosen to keep it
+/// within 64 bits, which leaves no room to scale.
+fn day13(rng: &mut Rng, _scale: usize) -> String {
+    let is_prime = |n: &usize| {
+        (2..)
+            .take_while(|d| d * d <= *n)
+            .all(|d| !n.is_multiple_of(d))
+    };
+    let mut small: Vec<usize> = (13..=53).filter(is_prime).collect();
+    let mut large: Vec<usize> = (401..=997).filter(is_prime).collect();
+    rng.shuffle(&mut small);
+    rng.shuffle(&mut large);
+    let mut ids: Vec<usize> = small
+        .into_iter()
+        .take(7)
+        .chain(large.into_iter().take(2))
+        .collect();
+    rng.shuffle(&mut ids);
+
+    let slots = rng.range(60..=80);
+    let mut positions: Vec<usize> = (1..slots).collect();
+    rng.shuffle(&mut positions);
+    positions.truncate(ids.len() - 1);
+    positions.push(0);
+
+    let mut schedule = vec!["x".to_string(); slots];
+    for (position, id) in positions.iter().zip(ids) {
+        schedule[*position] = id.to_string();
+    }
+    lines(vec![
+        rng.range(1_000_000..=1_010_000).to_string(),
+        schedule.join(","),
+    ])
+}
+
+/// Docking program of masks and memory writes.
+fn day14(rng: &mut Rng, scale: usize) -> String {
+    let mut program = Vec::new();
+    for _ in 0..100 * scale {
+        let mut mask: Vec<char> = (0..36)
+            .map(|_| if rng.chance(0.5) { '1' } else { '0' })
+            .collect();
+        // floating bits multiply part 2's writes, so keep them to a handful
+        let mut bits: Vec<usize> = (0..36).collect();
+        rng.shuffle(&mut bits);
+        for bit in bits.into_iter().take(rng.range(0..=9)) {
+            mask[bit] = 'X';
+        }
+        program.push(format!("mask = {}", mask.into_iter().collect::<String>()));
+        for _ in 0..rng.range(1..=6) {
+            program.push(format!(
+                "mem[{}] = {}",
+                rng.range(0..=65535),
+                rng.range(1..=999_999_999)
+            ));
+        }
+    }
+    lines(program)
+}
+
+/// Starting numbers for the memory game; these never grow.
+fn day15(rng: &mut Rng, _scale: usize) -> String {
+    let mut numbers: Vec<usize> = (0..=20).collect();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(rng.range(6..=7));
+    let numbers: Vec<String> = numbers.iter().map(ToString::to_string).collect();
+    lines(vec![numbers.join(",")])
+}
+
+/// Ticket rules, your ticket, and nearby tickets, about a quarter of which are invalid.
+///
+/// Fields are ranked, and each accepts a shared low range plus everything from its own band of
+/// high values upwards. A field's column always holds a value from its band, which no
+/// higher-ranked field accepts, so fields can be assigned to columns by elimination.
+fn day16(rng: &mut Rng, scale: usize) -> String {
+    const FIELDS: &[&str] = &[
+        "departure location",
+        "departure station",
+        "departure platform",
+        "departure track",
+        "departure date",
+        "departure time",
+        "arrival location",
+        "arrival station",
+        "arrival platform",
+        "arrival track",
+        "class",
+        "duration",
+        "price",
+        "route",
+        "row",
+        "seat",
+        "train",
+        "type",
+        "wagon",
+        "zone",
+    ];
+    const BAND_WIDTH: usize = 30;
+
+    let n = FIELDS.len();
+    let mut names = FIELDS.to_vec();
+    rng.shuffle(&mut names);
+    let mut columns: Vec<usize> = (0..n).collect();
+    rng.shuffle(&mut columns);
+
+    let band_start = rng.range(300..=320);
+    let band =
+        |rank: usize| band_start + rank * BAND_WIDTH..=band_start + (rank + 1) * BAND_WIDTH - 1;
+    let end = band_start + n * BAND_WIDTH - 1 + rng.range(0..=40);
+    let shared = rng.range(25..=50)..=rng.range(180..=260);
+    let low_ranges: Vec<(usize, usize)> = (0..n)
+        .map(|_| {
+            (
+                shared.start() - rng.range(0..=10),
+                shared.end() + rng.range(0..=20),
+            )
+        })
+        .collect();
+
+    let mut rules: Vec<String> = (0..n)
+        .map(|rank| {
+            let (lo, hi) = low_ranges[rank];
+            format!(
+                "{}: {}-{} or {}-{}",
+                names[rank],
+                lo,
+                hi,
+                band(rank).start(),
+                end
+            )
+        })
+        .collect();
+    rng.shuffle(&mut rules);
+
+    let ticket = |rng: &mut Rng, own_bands: bool| -> Vec<usize> {
+        let mut values = vec![0; n];
+        for rank in 0..n {
+            values[columns[rank]] = if own_bands {
+                rng.range(band(rank))
+            } else if rng.chance(0.3) {
+                let higher = rng.range(rank..=n - 1);
+                rng.range(band(higher))
+            } else {
+                rng.range(shared.clone())
+            };
+        }
+        values
+    };
+    let invalid = |rng: &mut Rng| {
+        let lowest = low_ranges.iter().map(|(lo, _)| *lo).min().unwrap_or(1);
+        let highest = low_ranges.iter().map(|(_, hi)| *hi).max().unwrap_or(1);
+        match rng.range(0..=2) {
+            0 => rng.range(0..=lowest - 1),
+            1 => rng.range(highest + 1..=band_start - 1),
+            _ => rng.range(end + 1..=999),
+        }
+    };
+
+    let own = ticket(rng, false);
+    let mut nearby = vec![ticket(rng, true)];
+    for _ in 1..240 * scale {
+        let mut values = ticket(rng, false);
+        if rng.chance(0.25) {
+            let column = rng.range(0..=n - 1);
+            values[column] = invalid(rng);
+        }
+        nearby.push(values);
+    }
+    rng.shuffle(&mut nearby);
+
+    let csv = |values: &Vec<usize>| {
+        values
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    format!(
+        "{}\nyour ticket:\n{}\n\nnearby tickets:\n{}",
+        lines(rules),
+        csv(&own),
+        lines(nearby.iter().map(csv))
+    )
+}
+
+/// Initial slice of the Conway cubes.
+fn day17(rng: &mut Rng, scale: usize) -> String {
+    let size = 8 * scale;
+    let cells: Vec<Vec<bool>> = (0..size)
+        .map(|_| (0..size).map(|_| rng.chance(0.45)).collect())
+        .collect();
+    lines(grid(&cells))
+}
+
+/// Homework of arithmetic expressions.
+fn day18(rng: &mut Rng, scale: usize) -> String {
+    /// Each digit spends some of the budget; keeping the number of digits small keeps results,
+    /// even when everything is multiplied, well within 64 bits.
+    fn expression(rng: &mut Rng, depth: usize, budget: &mut usize) -> String {
+        let terms = rng.range(2..=4).min((*budget).max(2));
+        let mut out = String::new();
+        for i in 0..terms {
+            if i > 0 {
+                out.push_str(if rng.chance(0.5) { " + " } else { " * " });
+            }
+            if depth < 2 && *budget >= 4 && rng.chance(0.3) {
+                let inner = expression(rng, depth + 1, budget);
+                out.push_str(&format!("({})", inner));
+            } else {
+                *budget = budget.saturating_sub(1);
+                out.push_str(&rng.range(1..=9).to_string());
+            }
+        }
+        out
+    }
+
+    lines((0..370 * scale).map(|_| expression(rng, 0, &mut 10)))
+}
+
+/// A rule body: either a literal character, or alternative sequences of other rules.
+enum Body {
+    Literal(char),
+    Alternatives(Vec<Vec<usize>>),
+}
+
+/// Builds rules matching exactly a given set of equal-length strings over `a` and `b`.
+///
+/// Rules are identified by their index into `bodies` until they are numbered for output.
+struct Grammar {
+    bodies: Vec<Body>,
+    known: HashMap<Vec<String>, usize>,
+}
+
+impl Grammar {
+    const A: usize = 0;
+    const B: usize = 1;
+
+    fn new() -> Grammar {
+        Grammar {
+            bodies: vec![Body::Literal('a'), Body::Literal('b')],
+            known: HashMap::new(),
+        }
+    }
+
+    /// `set` must be sorted, non-empty, and contain non-empty strings of equal length.
+    fn rule_for(&mut self, set: &[String]) -> usize {
+        if let Some(rule) = self.known.get(set) {
+            return *rule;
+        }
+        if set.len() == 1 && set[0] == "a" {
+            return Self::A;
+        }
+        if set.len() == 1 && set[0] == "b" {
+            return Self::B;
+        }
+
+        let mut alternatives = Vec::new();
+        for (letter, literal) in [('a', Self::A), ('b', Self::B)].iter() {
+            let rest: Vec<String> = set
+                .iter()
+                .filter_map(|s| s.strip_prefix(*letter))
+                .map(ToString::to_string)
+                .collect();
+            if rest.is_empty() {
+                continue;
+            }
+            if rest[0].is_empty() {
+                alternatives.push(vec![*literal]);
+            } else {
+                alternatives.push(vec![*literal, self.rule_for(&rest)]);
+            }
+        }
+
+        self.bodies.push(Body::Alternatives(alternatives));
+        let rule = self.bodies.len() - 1;
+        self.known.insert(set.to_vec(), rule);
+        rule
+    }
+}
+
+/// Message rules and messages.
+///
+/// Rules 42 and 31 match complementary halves of all strings of one length, so that the
+/// looping rules of part 2 stay unambiguous; the rest of the grammar is derived from them.
+fn day19(rng: &mut Rng, scale: usize) -> String {
+    const LEN: usize = 6;
+    const MAX_BLOCKS: usize = 7;
+
+    let mut all: Vec<String> = (0..1 << LEN)
+        .map(|bits: usize| {
+            (0..LEN)
+                .rev()
+                .map(|bit| if (bits >> bit) & 1 == 1 { 'b' } else { 'a' })
+                .collect()
+        })
+        .collect();
+    rng.shuffle(&mut all);
+    let (mut forty_two, mut thirty_one) =
+        (all[..all.len() / 2].to_vec(), all[all.len() / 2..].to_vec());
+    forty_two.sort();
+    thirty_one.sort();
+
+    let mut grammar = Grammar::new();
+    let rule_42 = grammar.rule_for(&forty_two);
+    let rule_31 = grammar.rule_for(&thirty_one);
+
+    const FIXED: [usize; 5] = [0, 8, 11, 31, 42];
+    let mut ids: Vec<usize> = (1..grammar.bodies.len() + FIXED.len() + 20)
+        .filter(|id| !FIXED.contains(id))
+        .collect();
+    rng.shuffle(&mut ids);
+    let mut id_of: Vec<usize> = ids.into_iter().take(grammar.bodies.len()).collect();
+    id_of[rule_42] = 42;
+    id_of[rule_31] = 31;
+
+    let mut rules = vec![
+        "0: 8 11".to_string(),
+        "8: 42".to_string(),
+        "11: 42 31".to_string(),
+    ];
+    for (rule, body) in grammar.bodies.iter().enumerate() {
+        let body = match body {
+            Body::Literal(c) => format!("\"{}\"", c),
+            Body::Alternatives(alternatives) => alternatives
+                .iter()
+                .map(|sequence| {
+                    sequence
+                        .iter()
+                        .map(|rule| id_of[*rule].to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join(" | "),
+        };
+        rules.push(format!("{}: {}", id_of[rule], body));
+    }
+    rng.shuffle(&mut rules);
+
+    let messages = (0..400 * scale).map(|_| {
+        let (forty_twos, thirty_ones) = match rng.range(0..=2) {
+            // valid in both parts
+            0 => (2, 1),
+            // valid only once rules 8 and 11 loop
+            1 => {
+                let thirty_ones = rng.range(1..=(MAX_BLOCKS - 1) / 2);
+                (
+                    rng.range(thirty_ones + 1..=MAX_BLOCKS - thirty_ones),
+                    thirty_ones,
+                )
+            }
+            // almost certainly valid in neither
+            _ => return word(rng, b"ab", LEN * 2..=LEN * MAX_BLOCKS),
+        };
+        let mut message = String::new();
+        for _ in 0..forty_twos {
+            message.push_str(rng.choose(&forty_two).as_str());
+        }
+        for _ in 0..thirty_ones {
+            message.push_str(rng.choose(&thirty_one).as_str());
+        }
+        message
+    });
+    let messages: Vec<String> = messages.collect();
+
+    format!("{}\n{}", lines(rules), lines(messages))
+}
+
+/// The sea monster, as it appears in the assembled image.
+const MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+/// Jigsaw tiles which assemble into an image containing sea monsters.
+///
+/// Tile edges must be unique, and there are only so many ten-cell edges, so this ignores `scale`.
+fn day20(rng: &mut Rng, _scale: usize) -> String {
+    loop {
+        if let Some(tiles) = jigsaw(rng) {
+            return tiles;
+        }
+    }
+}
+
+fn jigsaw(rng: &mut Rng) -> Option<String> {
+    const SIDE: usize = 12;
+    const TILE: usize = 10;
+    const INNER: usize = TILE - 2;
+
+    // corners are shared by up to four tiles, and edges by two
+    let corners: Vec<Vec<bool>> = (0..=SIDE)
+        .map(|_| (0..=SIDE).map(|_| rng.chance(0.5)).collect())
+        .collect();
+    let mut used = HashSet::new();
+    let mut horizontal = vec![vec![Vec::new(); SIDE]; SIDE + 1];
+    let mut vertical = vec![vec![Vec::new(); SIDE + 1]; SIDE];
+    for r in 0..=SIDE {
+        for c in 0..SIDE {
+            horizontal[r][c] = unique_edge(rng, &mut used, corners[r][c], corners[r][c + 1])?;
+        }
+    }
+    for r in 0..SIDE {
+        for c in 0..=SIDE {
+            vertical[r][c] = unique_edge(rng, &mut used, corners[r][c], corners[r + 1][c])?;
+        }
+    }
+
+    let size = SIDE * INNER;
+    let mut image: Vec<Vec<bool>> = (0..size)
+        .map(|_| (0..size).map(|_| rng.chance(0.25)).collect())
+        .collect();
+    let monster: Vec<Vec<bool>> = MONSTER
+        .iter()
+        .map(|row| row.chars().map(|c| c == '#').collect())
+        .collect();
+    let (height, width) = (monster.len(), monster[0].len());
+    let mut placed: Vec<(usize, usize)> = Vec::new();
+    for _ in 0..40 {
+        let (y, x) = (rng.range(0..=size - height), rng.range(0..=size - width));
+        // keep a cell of clearance, so that monsters never share a line of the image
+        let clear = placed.iter().all(|&(py, px)| {
+            y > py + height || py > y + height || x > px + width || px > x + width
+        });
+        if !clear {
+            continue;
+        }
+        for (dy, row) in monster.iter().enumerate() {
+            for (dx, cell) in row.iter().enumerate() {
+                image[y + dy][x + dx] |= *cell;
+            }
+        }
+        placed.push((y, x));
+    }
+
+    // the day's solver picks an orientation in which the middle of a monster appears anywhere,
+    // so that must happen only where monsters were actually placed; a monster's middle is
+    // symmetric top to bottom, so it also appears when the image is flipped that way
+    let mut expected: Vec<(usize, usize)> = placed.iter().map(|(y, x)| (y + 1, *x)).collect();
+    let mut flipped: Vec<(usize, usize)> =
+        expected.iter().map(|(y, x)| (size - 1 - y, *x)).collect();
+    expected.sort_unstable();
+    flipped.sort_unstable();
+    for (idx, orientation) in orientations(&image).iter().enumerate() {
+        let matches = monster_middles(orientation, &monster[1]);
+        let allowed = match idx {
+            0 => &expected,
+            FLIPPED_VERTICALLY => &flipped,
+            _ => &Vec::new(),
+        };
+        if &matches != allowed {
+            return None;
+        }
+    }
+
+    let mut ids = HashSet::new();
+    while ids.len() < SIDE * SIDE {
+        ids.insert(rng.range(1000..=9999));
+    }
+    let mut ids: Vec<usize> = ids.into_iter().collect();
+    ids.sort_unstable();
+    rng.shuffle(&mut ids);
+
+    let mut tiles = Vec::new();
+    for r in 0..SIDE {
+        for c in 0..SIDE {
+            let cells: Vec<Vec<bool>> = (0..TILE)
+                .map(|i| {
+                    (0..TILE)
+                        .map(|j| match (i, j) {
+                            (0, _) => horizontal[r][c][j],
+                            (i, _) if i == TILE - 1 => horizontal[r + 1][c][j],
+                            (_, 0) => vertical[r][c][i],
+                            (_, j) if j == TILE - 1 => vertical[r][c + 1][i],
+                            (i, j) => image[r * INNER + i - 1][c * INNER + j - 1],
+                        })
+                        .collect()
+                })
+                .collect();
+            let orientation = rng.range(0..=7);
+            let cells = orientations(&cells).swap_remove(orientation);
+            tiles.push(format!(
+                "Tile {}:\n{}",
+                ids[r * SIDE + c],
+                lines(grid(&cells))
+            ));
+        }
+    }
+    rng.shuffle(&mut tiles);
+    Some(tiles.join("\n"))
+}
+
+/// A random edge from `start` to `end` which is no palindrome, and not yet used either way round.
+fn unique_edge(
+    rng: &mut Rng,
+    used: &mut HashSet<Vec<bool>>,
+    start: bool,
+    end: bool,
+) -> Option<Vec<bool>> {
+    (0..1000).find_map(|_| {
+        let mut edge = vec![start];
+        edge.extend((0..8).map(|_| rng.chance(0.5)));
+        edge.push(end);
+        let reversed: Vec<bool> = edge.iter().rev().copied().collect();
+        if edge == reversed || used.contains(&edge) || used.contains(&reversed) {
+            return None;
+        }
+        used.insert(edge.clone());
+        Some(edge)
+    })
+}
+
+/// The index, among [`orientations`], of the grid flipped top to bottom.
+const FLIPPED_VERTICALLY: usize = 4;
+
+/// All eight rotations and reflections of a square grid, starting with the grid itself.
+fn orientations(cells: &[Vec<bool>]) -> Vec<Vec<Vec<bool>>> {
+    let rotate = |cells: &[Vec<bool>]| -> Vec<Vec<bool>> {
+        (0..cells.len())
+            .map(|i| {
+                (0..cells.len())
+                    .map(|j| cells[cells.len() - 1 - j][i])
+                    .collect()
+            })
+            .collect()
+    };
+    let mut all = vec![cells.to_vec()];
+    for _ in 0..3 {
+        let next = rotate(all.last().expect("never empty"));
+        all.push(next);
+    }
+    for idx in 0..4 {
+        let flipped = all[idx].iter().rev().cloned().collect();
+        all.push(flipped);
+    }
+    all
+}
+
+/// Positions at which `middle` (the middle line of a monster) matches, ignoring blank cells.
+fn monster_middles(cells: &[Vec<bool>], middle: &[bool]) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    for (y, row) in cells.iter().enumerate() {
+        for x in 0..=row.len() - middle.len() {
+            if middle.iter().enumerate().all(|(dx, m)| !m || row[x + dx]) {
+                matches.push((y, x));
+            }
+        }
+    }
+    matches
+}
+
+/// Foods, their ingredients, and some of their allergens.
+fn day21(rng: &mut Rng, scale: usize) -> String {
+    loop {
+        if let Some(foods) = allergen_foods(rng, scale) {
+            return lines(foods);
+        }
+    }
+}
+
+/// Returns `None` unless allergens can all be pinned to ingredients by elimination.
+fn allergen_foods(rng: &mut Rng, scale: usize) -> Option<Vec<String>> {
+    const ALLERGENS: &[&str] = &[
+        "dairy",
+        "eggs",
+        "fish",
+        "nuts",
+        "peanuts",
+        "sesame",
+        "shellfish",
+        "soy",
+        "wheat",
+    ];
+
+    let mut allergens = ALLERGENS.to_vec();
+    rng.shuffle(&mut allergens);
+    allergens.truncate(8);
+
+    let mut ingredients = HashSet::new();
+    while ingredients.len() < 200 {
+        ingredients.insert(word(rng, LETTERS, 4..=8));
+    }
+    let mut ingredients: Vec<String> = ingredients.into_iter().collect();
+    ingredients.sort();
+    rng.shuffle(&mut ingredients);
+    let (culprits, safe) = ingredients.split_at(allergens.len());
+
+    let mut foods = Vec::new();
+    for _ in 0..40 * scale {
+        let mut listed: Vec<usize> = (0..allergens.len()).collect();
+        rng.shuffle(&mut listed);
+        listed.truncate(rng.range(1..=3));
+
+        let mut contents: HashSet<&String> = listed.iter().map(|a| &culprits[*a]).collect();
+        // allergens aren't always listed
+        for culprit in culprits {
+            if rng.chance(0.2) {
+                contents.insert(culprit);
+            }
+        }
+        for _ in 0..rng.range(8..=20) {
+            contents.insert(rng.choose(safe));
+        }
+        let mut contents: Vec<&String> = contents.into_iter().collect();
+        contents.sort();
+        rng.shuffle(&mut contents);
+        foods.push((contents, listed));
+    }
+
+    // replicate the elimination the day's solver performs
+    let mut candidates: Vec<Option<HashSet<&String>>> = vec![None; allergens.len()];
+    for (contents, listed) in &foods {
+        let contents: HashSet<&String> = contents.iter().copied().collect();
+        for allergen in listed {
+            candidates[*allergen] = Some(match candidates[*allergen].take() {
+                Some(previous) => previous.intersection(&contents).copied().collect(),
+                None => contents.clone(),
+            });
+        }
+    }
+    let mut candidates: Vec<HashSet<&String>> = candidates.into_iter().collect::<Option<_>>()?;
+    let mut identified = 0;
+    while identified < allergens.len() {
+        let known: Vec<&String> = candidates
+            .iter()
+            .filter(|c| c.len() == 1)
+            .flat_map(|c| c.iter().copied())
+            .collect();
+        if known.len() == identified {
+            return None;
+        }
+        identified = known.len();
+        for c in candidates.iter_mut().filter(|c| c.len() > 1) {
+            c.retain(|i| !known.contains(i));
+        }
+    }
+
+    Some(
+        foods
+            .iter()
+            .map(|(contents, listed)| {
+                let contents: Vec<&str> = contents.iter().map(|s| s.as_str()).collect();
+                let listed: Vec<&str> = listed.iter().map(|a| allergens[*a]).collect();
+                format!("{} (contains {})", contents.join(" "), listed.join(", "))
+            })
+            .collect(),
+    )
+}
+
+/// Two decks of space cards.
+fn day22(rng: &mut Rng, scale: usize) -> String {
+    let mut cards: Vec<usize> = (1..=50 * scale).collect();
+    rng.shuffle(&mut cards);
+    let (one, two) = cards.split_at(cards.len() / 2);
+    format!(
+        "Player 1:\n{}\nPlayer 2:\n{}",
+        lines(one.iter().map(ToString::to_string)),
+        lines(two.iter().map(ToString::to_string))
+    )
+}
+
+/// Cup labels; there are always nine.
+fn day23(rng: &mut Rng, _scale: usize) -> String {
+    let mut cups: Vec<usize> = (1..=9).collect();
+    rng.shuffle(&mut cups);
+    lines(vec![cups
+        .iter()
+        .map(ToString::to_string)
+        .collect::<String>()])
+}
+
+/// Hexagonal tile paths.
+fn day24(rng: &mut Rng, scale: usize) -> String {
+    const DIRECTIONS: &[&str] = &["e", "se", "sw", "w", "nw", "ne"];
+    lines((0..400 * scale).map(|_| {
+        (0..rng.range(15..=25))
+            .map(|_| *rng.choose(DIRECTIONS))
+            .collect::<String>()
+    }))
+}
+
+/// Card and door public keys; their loop sizes are what the puzzle is about, not the input size.
+fn day25(rng: &mut Rng, _scale: usize) -> String {
+    const MODULUS: u64 = 20201227;
+    let transform = |loop_size: usize| (0..loop_size).fold(1, |value: u64, _| value * 7 % MODULUS);
+
+    let card = rng.range(100_000..=10_000_000);
+    let door = loop {
+        let door = rng.range(100_000..=10_000_000);
+        if door != card {
+            break door;
+        }
+    };
+    lines(vec![
+        transform(card).to_string(),
+        transform(door).to_string(),
+    ])
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("no generator for day {0}")]
+    NoGenerator(u8),
+    #[error("scale must be at least 1")]
+    ZeroScale,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_deterministic(day: u8) {
+        let input = generate(day, 7, 1).unwrap();
+        assert!(!input.is_empty(), "day {} generated nothing", day);
+        assert_eq!(
+            input,
+            generate(day, 7, 1).unwrap(),
+            "day {} isn't deterministic",
+            day
+        );
+    }
+
+    #[test]
+    fn test_deterministic() {
+        // day 11 simulates its layout to settle it, which is slow unoptimized; it gets a test
+        // of its own so that it runs alongside the rest
+        for day in (1..=25).filter(|day| *day != 11) {
+            assert_deterministic(day);
+            assert_ne!(
+                generate(day, 7, 1).unwrap(),
+                generate(day, 8, 1).unwrap(),
+                "day {} ignores its seed",
+                day
+            );
+        }
+    }
+
+    #[test]
+    fn test_deterministic_seating() {
+        assert_deterministic(11);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(generate(26, 1, 1), Err(Error::NoGenerator(26))));
+        assert!(matches!(generate(1, 1, 0), Err(Error::ZeroScale)));
+    }
+}
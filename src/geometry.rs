@@ -1,10 +1,14 @@
-use bitvec::bitvec;
+use crate::math::gcd;
+use bitvec::{bitvec, vec::BitVec};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+#[cfg(feature = "geometry-serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque};
 use std::convert::TryFrom;
 use std::fmt;
-use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Sub};
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign};
 use std::str::FromStr;
 
 pub fn follow(traces: &[Trace]) -> Vec<Line> {
@@ -25,35 +29,136 @@ pub fn follow(traces: &[Trace]) -> Vec<Line> {
     out
 }
 
-// https://stackoverflow.com/a/1968345/504550
-pub fn intersect(a: Line, b: Line) -> Option<Point> {
-    let p0 = a.from;
-    let p1 = a.to;
-    let p2 = b.from;
-    let p3 = b.to;
+/// Where two [`Line`] segments meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub enum Intersection {
+    /// The segments meet at a single point of the integer grid.
+    Point(Point),
+    /// The segments cross at a single point between grid points: `(x / denominator, y / denominator)`,
+    /// in lowest terms with a positive denominator.
+    Fraction { x: i128, y: i128, denominator: i128 },
+    /// The segments are collinear and share this sub-segment, which is longer than a point.
+    Overlap(Line),
+}
 
-    let s1_x = (p1.x - p0.x) as f32;
-    let s1_y = (p1.y - p0.y) as f32;
-    let s2_x = (p3.x - p2.x) as f32;
-    let s2_y = (p3.y - p2.y) as f32;
+impl Intersection {
+    /// Every grid point shared by the segments.
+    pub fn lattice_points(&self) -> Vec<Point> {
+        match *self {
+            Intersection::Point(point) => vec![point],
+            Intersection::Fraction { .. } => Vec::new(),
+            Intersection::Overlap(line) => {
+                let delta = line.to - line.from;
+                let steps =
+                    gcd(delta.x.unsigned_abs().into(), delta.y.unsigned_abs().into()) as i32;
+                let step = delta / steps;
+                (0..=steps).map(|n| line.from + step * n).collect()
+            }
+        }
+    }
+}
 
-    let s =
-        (-s1_y * (p0.x - p2.x) as f32 + s1_x * (p0.y - p2.y) as f32) / (-s2_x * s1_y + s1_x * s2_y);
-    let t =
-        (s2_x * (p0.y - p2.y) as f32 - s2_y * (p0.x - p2.x) as f32) / (-s2_x * s1_y + s1_x * s2_y);
+fn cross((ax, ay): (i128, i128), (bx, by): (i128, i128)) -> i128 {
+    ax * by - ay * bx
+}
 
-    if s >= 0.0 && s <= 1.0 && t >= 0.0 && t <= 1.0 {
-        // round the results so errors line up nicely
-        Some(Point::new(
-            p0.x + (t * s1_x).round() as i32,
-            p0.y + (t * s1_y).round() as i32,
-        ))
+fn dot((ax, ay): (i128, i128), (bx, by): (i128, i128)) -> i128 {
+    ax * bx + ay * by
+}
+
+fn wide(point: Point) -> (i128, i128) {
+    (point.x as i128, point.y as i128)
+}
+
+fn delta(from: Point, to: Point) -> (i128, i128) {
+    let ((x0, y0), (x1, y1)) = (wide(from), wide(to));
+    (x1 - x0, y1 - y0)
+}
+
+/// Find where two segments meet, using exact integer arithmetic.
+///
+/// Segments include both endpoints, and may be single points.
+pub fn intersect(a: Line, b: Line) -> Option<Intersection> {
+    let d1 = delta(a.from, a.to);
+    let d2 = delta(b.from, b.to);
+    let r = delta(a.from, b.from);
+    let mut denominator = cross(d1, d2);
+
+    if denominator == 0 {
+        return intersect_parallel(a, b);
+    }
+
+    // a.from + t * d1 == b.from + u * d2, for t and u in [0, 1]
+    // products of coordinate differences need 66 bits, and the numerators below more still
+    let mut t = cross(r, d2);
+    let mut u = cross(r, d1);
+    if denominator < 0 {
+        denominator = -denominator;
+        t = -t;
+        u = -u;
+    }
+    if !(0..=denominator).contains(&t) || !(0..=denominator).contains(&u) {
+        return None;
+    }
+
+    let (x0, y0) = wide(a.from);
+    let x = x0 * denominator + d1.0 * t;
+    let y = y0 * denominator + d1.1 * t;
+    // the denominator is positive, so the divisor is too and fits back into an i128
+    let divisor = gcd(gcd(x.unsigned_abs(), y.unsigned_abs()), denominator as u128) as i128;
+    let (x, y, denominator) = (x / divisor, y / divisor, denominator / divisor);
+    Some(if denominator == 1 {
+        Intersection::Point(Point::new(x as i32, y as i32))
     } else {
-        None
+        Intersection::Fraction { x, y, denominator }
+    })
+}
+
+fn intersect_parallel(a: Line, b: Line) -> Option<Intersection> {
+    let direction = [a, b]
+        .iter()
+        .map(|line| delta(line.from, line.to))
+        .find(|&delta| delta != (0, 0));
+    let direction = match direction {
+        Some(direction) => direction,
+        None => return Some(Intersection::Point(a.from)).filter(|_| a.from == b.from),
+    };
+    // parallel lines which share a point share every point
+    if cross(delta(a.from, b.from), direction) != 0 || cross(delta(a.from, b.to), direction) != 0 {
+        return None;
+    }
+
+    let along = |point: Point| dot(delta(a.from, point), direction);
+    let ends = |line: Line| {
+        if along(line.from) <= along(line.to) {
+            (line.from, line.to)
+        } else {
+            (line.to, line.from)
+        }
+    };
+    let (a_low, a_high) = ends(a);
+    let (b_low, b_high) = ends(b);
+    let low = if along(a_low) >= along(b_low) {
+        a_low
+    } else {
+        b_low
+    };
+    let high = if along(a_high) <= along(b_high) {
+        a_high
+    } else {
+        b_high
+    };
+
+    match along(low).cmp(&along(high)) {
+        std::cmp::Ordering::Greater => None,
+        std::cmp::Ordering::Equal => Some(Intersection::Point(low)),
+        std::cmp::Ordering::Less => Some(Intersection::Overlap(Line::new(low, high))),
     }
 }
 
-pub fn intersections_naive(ap: &[Line], bp: &[Line]) -> Vec<Point> {
+/// Every intersection between a segment of `ap` and a segment of `bp`, by comparing every pair.
+pub fn intersections_naive(ap: &[Line], bp: &[Line]) -> Vec<Intersection> {
     let mut isects = Vec::new();
     for a in ap {
         for b in bp {
@@ -65,7 +170,92 @@ pub fn intersections_naive(ap: &[Line], bp: &[Line]) -> Vec<Point> {
     isects
 }
 
+/// Every intersection between a segment of `ap` and a segment of `bp`, found with a sweep line.
+///
+/// The sweep moves along x, keeping the segments which span it. Horizontal segments are indexed
+/// by y, so the axis-aligned wires produced by [`follow`] take `O((n + k) log n)` time for `k`
+/// intersections; other segments are compared with everything their x-extent overlaps.
+///
+/// Finds the same intersections as [`intersections_naive`], in a different order.
+pub fn intersections(ap: &[Line], bp: &[Line]) -> Vec<Intersection> {
+    #[derive(Default)]
+    struct Active {
+        /// Horizontal segments by y.
+        horizontal: BTreeMap<i32, Vec<Line>>,
+        others: Vec<Line>,
+    }
+
+    let x_range = |line: &Line| (line.from.x.min(line.to.x), line.from.x.max(line.to.x));
+    let y_range = |line: &Line| (line.from.y.min(line.to.y), line.from.y.max(line.to.y));
+    let is_horizontal = |line: &Line| line.from.y == line.to.y && line.from.x != line.to.x;
+
+    // (x, removal, set, segment): at equal x, every segment is inserted before any is removed
+    let mut events = Vec::with_capacity(2 * (ap.len() + bp.len()));
+    for (set, lines) in [ap, bp].iter().enumerate() {
+        for &line in lines.iter() {
+            let (x0, x1) = x_range(&line);
+            events.push((x0, false, set, line));
+            events.push((x1, true, set, line));
+        }
+    }
+    events.sort_unstable();
+
+    let mut active = [Active::default(), Active::default()];
+    let mut isects = Vec::new();
+    for (_, removal, set, line) in events {
+        if removal {
+            let active = &mut active[set];
+            let bucket = if is_horizontal(&line) {
+                active
+                    .horizontal
+                    .get_mut(&line.from.y)
+                    .expect("horizontal segments are inserted before removal")
+            } else {
+                &mut active.others
+            };
+            let idx = bucket
+                .iter()
+                .position(|&other| other == line)
+                .expect("segments are inserted before removal");
+            bucket.swap_remove(idx);
+            if bucket.is_empty() && is_horizontal(&line) {
+                active.horizontal.remove(&line.from.y);
+            }
+            continue;
+        }
+
+        // each pair is compared once, when the later of the two is inserted
+        let opposite = &active[1 - set];
+        let (y0, y1) = y_range(&line);
+        let candidates = opposite.others.iter().chain(
+            opposite
+                .horizontal
+                .range(y0..=y1)
+                .flat_map(|(_, lines)| lines),
+        );
+        for &other in candidates {
+            let (a, b) = if set == 0 {
+                (line, other)
+            } else {
+                (other, line)
+            };
+            if let Some(isect) = intersect(a, b) {
+                isects.push(isect);
+            }
+        }
+
+        let active = &mut active[set];
+        if is_horizontal(&line) {
+            active.horizontal.entry(line.from.y).or_default().push(line);
+        } else {
+            active.others.push(line);
+        }
+    }
+    isects
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
 pub enum Direction {
     Right,
     Left,
@@ -105,12 +295,182 @@ impl Direction {
         }
     }
 
+    /// Turn clockwise by `degrees`, which must be a multiple of 90; negative turns go anticlockwise.
+    pub fn turn(self, degrees: i32) -> Option<Direction> {
+        if degrees % 90 != 0 {
+            return None;
+        }
+        Direction::try_from(Compass::from(self).turn(degrees)?).ok()
+    }
+
     pub fn iter() -> impl Iterator<Item = Direction> {
         use Direction::*;
         [Up, Down, Left, Right].iter().cloned()
     }
 }
 
+/// A compass direction, including the diagonals.
+///
+/// North is +y and East is +x.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub enum Compass {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Compass {
+    const CLOCKWISE: [Compass; 8] = [
+        Compass::North,
+        Compass::NorthEast,
+        Compass::East,
+        Compass::SouthEast,
+        Compass::South,
+        Compass::SouthWest,
+        Compass::West,
+        Compass::NorthWest,
+    ];
+
+    /// (dx, dy); diagonals move one step along both axes.
+    pub fn deltas(self) -> (i32, i32) {
+        use Compass::*;
+        match self {
+            North => (0, 1),
+            NorthEast => (1, 1),
+            East => (1, 0),
+            SouthEast => (1, -1),
+            South => (0, -1),
+            SouthWest => (-1, -1),
+            West => (-1, 0),
+            NorthWest => (-1, 1),
+        }
+    }
+
+    /// Eighths of a turn clockwise from North.
+    fn eighths(self) -> i32 {
+        Self::CLOCKWISE
+            .iter()
+            .position(|&compass| compass == self)
+            .expect("every compass direction is listed") as i32
+    }
+
+    /// Turn clockwise by `degrees`, which must be a multiple of 45; negative turns go anticlockwise.
+    pub fn turn(self, degrees: i32) -> Option<Compass> {
+        if degrees % 45 != 0 {
+            return None;
+        }
+        let eighths = (self.eighths() + degrees / 45).rem_euclid(8);
+        Some(Self::CLOCKWISE[eighths as usize])
+    }
+
+    pub fn turn_right(self) -> Compass {
+        self.turn(90).expect("90 is a multiple of 45")
+    }
+
+    pub fn turn_left(self) -> Compass {
+        self.turn(-90).expect("90 is a multiple of 45")
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.eighths() % 2 == 1
+    }
+
+    /// All eight directions, clockwise from North.
+    pub fn iter() -> impl Iterator<Item = Compass> {
+        Self::CLOCKWISE.iter().cloned()
+    }
+
+    /// `N`, `E`, `S` or `W`, in either case.
+    pub fn from_letter(letter: char) -> Option<Compass> {
+        match letter.to_ascii_uppercase() {
+            'N' => Some(Compass::North),
+            'E' => Some(Compass::East),
+            'S' => Some(Compass::South),
+            'W' => Some(Compass::West),
+            _ => None,
+        }
+    }
+}
+
+impl From<Direction> for Compass {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Compass::North,
+            Direction::Right => Compass::East,
+            Direction::Down => Compass::South,
+            Direction::Left => Compass::West,
+        }
+    }
+}
+
+impl TryFrom<Compass> for Direction {
+    type Error = Compass;
+
+    /// Fails for diagonals, returning them unchanged.
+    fn try_from(compass: Compass) -> Result<Self, Self::Error> {
+        match compass {
+            Compass::North => Ok(Direction::Up),
+            Compass::East => Ok(Direction::Right),
+            Compass::South => Ok(Direction::Down),
+            Compass::West => Ok(Direction::Left),
+            diagonal => Err(diagonal),
+        }
+    }
+}
+
+impl FromStr for Compass {
+    type Err = String;
+
+    /// `N`, `NE`, `E`, ... in either case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Compass::*;
+        match s.to_ascii_uppercase().as_str() {
+            "N" => Ok(North),
+            "NE" => Ok(NorthEast),
+            "E" => Ok(East),
+            "SE" => Ok(SouthEast),
+            "S" => Ok(South),
+            "SW" => Ok(SouthWest),
+            "W" => Ok(West),
+            "NW" => Ok(NorthWest),
+            _ => Err(format!("unknown compass direction: {}", s)),
+        }
+    }
+}
+
+/// A turn to the left or right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Turn {
+    /// `L` or `R`, in either case.
+    pub fn from_letter(letter: char) -> Option<Turn> {
+        match letter.to_ascii_uppercase() {
+            'L' => Some(Turn::Left),
+            'R' => Some(Turn::Right),
+            _ => None,
+        }
+    }
+
+    /// Signed degrees clockwise, as accepted by [`Compass::turn`] and [`Point::rotate`].
+    pub fn degrees(self, amount: i32) -> i32 {
+        match self {
+            Turn::Left => -amount,
+            Turn::Right => amount,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct Trace {
     direction: Direction,
@@ -142,6 +502,7 @@ impl FromStr for Trace {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
 pub struct Point {
     pub x: i32,
     pub y: i32,
@@ -167,6 +528,24 @@ impl Point {
             y: self.y.abs(),
         }
     }
+
+    /// Rotate clockwise about the origin by `degrees`, which must be a multiple of 90; negative
+    /// rotations go anticlockwise.
+    pub fn rotate(self, degrees: i32) -> Option<Point> {
+        if degrees % 90 != 0 {
+            return None;
+        }
+        let mut point = self;
+        for _ in 0..(degrees / 90).rem_euclid(4) {
+            point = Point::new(point.y, -point.x);
+        }
+        Some(point)
+    }
+
+    /// Rotate clockwise about `centre` by `degrees`, as for [`rotate`](Self::rotate).
+    pub fn rotate_about(self, centre: Point, degrees: i32) -> Option<Point> {
+        Some((self - centre).rotate(degrees)? + centre)
+    }
 }
 
 impl From<(usize, usize)> for Point {
@@ -178,6 +557,14 @@ impl From<(usize, usize)> for Point {
     }
 }
 
+impl From<Compass> for Point {
+    /// A unit step in that direction.
+    fn from(compass: Compass) -> Self {
+        let (x, y) = compass.deltas();
+        Point::new(x, y)
+    }
+}
+
 impl Add for Point {
     type Output = Point;
 
@@ -208,6 +595,14 @@ impl Add<Direction> for Point {
     }
 }
 
+impl Add<Compass> for Point {
+    type Output = Point;
+
+    fn add(self, compass: Compass) -> Point {
+        self + compass.deltas()
+    }
+}
+
 impl Sub for Point {
     type Output = Point;
 
@@ -241,7 +636,8 @@ impl Div<i32> for Point {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
 pub struct Line {
     pub from: Point,
     pub to: Point,
@@ -257,183 +653,728 @@ impl Line {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-pub struct Vector3 {
-    pub x: i32,
-    pub y: i32,
-    pub z: i32,
+/// A point in `D` dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PointN<const D: usize>(pub [i32; D]);
+
+impl<const D: usize> PointN<D> {
+    pub const fn new(coords: [i32; D]) -> PointN<D> {
+        PointN(coords)
+    }
+
+    /// Distance from the origin moving only along axes.
+    pub fn manhattan(&self) -> i32 {
+        self.0.iter().map(|c| c.abs()).sum()
+    }
+
+    /// Distance from the origin moving diagonally as well as along axes.
+    pub fn chebyshev(&self) -> i32 {
+        self.0.iter().map(|c| c.abs()).max().unwrap_or_default()
+    }
+
+    /// All `3^D - 1` points which differ from this one by at most 1 on every axis.
+    pub fn neighbours(&self) -> impl Iterator<Item = PointN<D>> {
+        let point = *self;
+        let count = 3_usize.pow(D as u32);
+        (0..count)
+            .filter(move |idx| *idx != count / 2)
+            .map(move |mut idx| {
+                let mut neighbour = point;
+                for coord in neighbour.0.iter_mut() {
+                    *coord += (idx % 3) as i32 - 1;
+                    idx /= 3;
+                }
+                neighbour
+            })
+    }
+
+    /// The `2D` points which differ from this one by 1 on a single axis.
+    pub fn orthogonal_neighbours(&self) -> impl Iterator<Item = PointN<D>> {
+        let point = *self;
+        (0..2 * D).map(move |idx| {
+            let mut neighbour = point;
+            neighbour.0[idx / 2] += if idx % 2 == 0 { -1 } else { 1 };
+            neighbour
+        })
+    }
+}
+
+impl<const D: usize> Default for PointN<D> {
+    fn default() -> Self {
+        PointN([0; D])
+    }
+}
+
+// serde only implements its traits for arrays of particular lengths, so these go by hand
+#[cfg(feature = "geometry-serde")]
+impl<const D: usize> Serialize for PointN<D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeTuple;
+        let mut tuple = serializer.serialize_tuple(D)?;
+        for coord in &self.0 {
+            tuple.serialize_element(coord)?;
+        }
+        tuple.end()
+    }
 }
 
-impl Vector3 {
-    pub fn new(x: i32, y: i32, z: i32) -> Vector3 {
-        Vector3 { x, y, z }
+#[cfg(feature = "geometry-serde")]
+impl<'de, const D: usize> Deserialize<'de> for PointN<D> {
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        use serde::de::Error;
+        let coords = Vec::<i32>::deserialize(deserializer)?;
+        let len = coords.len();
+        <[i32; D]>::try_from(coords)
+            .map(PointN)
+            .map_err(|_| De::Error::invalid_length(len, &format!("{} coordinates", D).as_str()))
     }
+}
+
+impl From<Point> for PointN<2> {
+    fn from(point: Point) -> Self {
+        PointN([point.x, point.y])
+    }
+}
 
-    pub fn abs_sum(&self) -> i32 {
-        self.x.abs() + self.y.abs() + self.z.abs()
+impl From<PointN<2>> for Point {
+    fn from(point: PointN<2>) -> Self {
+        Point::new(point.0[0], point.0[1])
     }
 }
 
 lazy_static! {
-    static ref VEC3_RE: Regex = Regex::new(
-        r"(?i)<\s*(x=\s*)?(?P<x>-?\d+),\s*(y=\s*)?(?P<y>-?\d+),\s*(z=\s*)?(?P<z>-?\d+)\s*>"
-    )
-    .unwrap();
+    static ref INT_RE: Regex = Regex::new(r"-?\d+").unwrap();
 }
 
-impl FromStr for Vector3 {
+/// Parse the first `D` integers of a string, ignoring any punctuation or labels around them:
+/// `1,2,3`, `(1, 2, 3)` and `<x=1, y=2, z=3>` are all the same point.
+impl<const D: usize> FromStr for PointN<D> {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let captures = VEC3_RE.captures(s).ok_or(format!("no regex match"))?;
-        Ok(Vector3 {
-            x: captures
-                .name("x")
-                .unwrap()
-                .as_str()
-                .parse()
-                .map_err(|err| format!("x: {}", err))?,
-            y: captures
-                .name("y")
-                .unwrap()
+        let mut point = PointN::default();
+        let mut ints = INT_RE.find_iter(s);
+        for (axis, coord) in point.0.iter_mut().enumerate() {
+            let int = ints
+                .next()
+                .ok_or_else(|| format!("expected {} coordinates; found {}", D, axis))?;
+            *coord = int
                 .as_str()
                 .parse()
-                .map_err(|err| format!("y: {}", err))?,
-            z: captures
-                .name("z")
-                .unwrap()
-                .as_str()
-                .parse()
-                .map_err(|err| format!("z: {}", err))?,
-        })
+                .map_err(|err| format!("axis {}: {}", axis, err))?;
+        }
+        if ints.next().is_some() {
+            return Err(format!(
+                "expected {} coordinates; found {}",
+                D,
+                D + 1 + ints.count()
+            ));
+        }
+        Ok(point)
     }
 }
 
-impl fmt::Display for Vector3 {
+impl<const D: usize> fmt::Display for PointN<D> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "<x={:3}, y={:3}, z={:3}>", self.x, self.y, self.z)
+        write!(f, "(")?;
+        for (axis, coord) in self.0.iter().enumerate() {
+            if axis > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", coord)?;
+        }
+        write!(f, ")")
     }
 }
 
-impl AddAssign for Vector3 {
-    fn add_assign(&mut self, other: Self) {
-        self.x += other.x;
-        self.y += other.y;
-        self.z += other.z;
+impl<const D: usize> Index<usize> for PointN<D> {
+    type Output = i32;
+
+    fn index(&self, axis: usize) -> &i32 {
+        &self.0[axis]
     }
 }
 
-impl Add for Vector3 {
-    type Output = Vector3;
+impl<const D: usize> IndexMut<usize> for PointN<D> {
+    fn index_mut(&mut self, axis: usize) -> &mut i32 {
+        &mut self.0[axis]
+    }
+}
 
-    fn add(self, other: Self) -> Self {
-        Vector3 {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            z: self.z + other.z,
+impl<const D: usize> AddAssign for PointN<D> {
+    fn add_assign(&mut self, other: Self) {
+        for (coord, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *coord += other;
         }
     }
 }
 
-/// A Map keeps track of a tile grid.
-///
-/// It is based on immutable data structures, so is cheap to clone.
-///
-/// Its coordinate system assumes that the origin is in the lower left,
-/// for compatibility with Direction.
-#[derive(Clone, Default)]
-pub struct Map<T: Clone> {
-    tiles: Vec<T>,
-    width: usize,
-    height: usize,
+impl<const D: usize> SubAssign for PointN<D> {
+    fn sub_assign(&mut self, other: Self) {
+        for (coord, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *coord -= other;
+        }
+    }
 }
 
-impl<T: Clone + Default> Map<T> {
-    pub fn new(width: usize, height: usize) -> Map<T> {
-        Map {
-            tiles: vec![T::default(); width * height].into(),
-            width,
-            height,
-        }
+impl<const D: usize> Add for PointN<D> {
+    type Output = PointN<D>;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
     }
 }
 
-impl<T: Clone> Map<T> {
-    pub fn iter(&self) -> impl Iterator<Item = &T> {
-        self.tiles.iter()
+impl<const D: usize> Sub for PointN<D> {
+    type Output = PointN<D>;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
     }
 }
 
-impl<T: Clone + std::hash::Hash> std::hash::Hash for Map<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.tiles.hash(state);
-        self.width.hash(state);
-        self.height.hash(state);
+impl<const D: usize> Mul<i32> for PointN<D> {
+    type Output = PointN<D>;
+
+    fn mul(mut self, other: i32) -> Self {
+        for coord in self.0.iter_mut() {
+            *coord *= other;
+        }
+        self
     }
 }
 
-impl<T: Clone + PartialEq> PartialEq for Map<T> {
-    fn eq(&self, other: &Self) -> bool {
-        self.width == other.width && self.height == other.height && self.tiles == other.tiles
+impl<const D: usize> Div<i32> for PointN<D> {
+    type Output = PointN<D>;
+
+    fn div(mut self, other: i32) -> Self {
+        for coord in self.0.iter_mut() {
+            *coord /= other;
+        }
+        self
     }
 }
 
-impl<T: Clone + Eq> Eq for Map<T> {}
+impl<const D: usize> Neg for PointN<D> {
+    type Output = PointN<D>;
 
-impl<T, R> From<&[R]> for Map<T>
-where
-    T: Clone,
-    R: AsRef<[T]>,
-{
-    /// Convert an input 2d array into a map.
-    ///
-    /// Note that the input array must already be arranged with the y axis
-    /// as the outer array and the orientation such that source[0][0] is the
-    /// lower left corner of the map.
-    ///
-    /// Panics if the input array is not rectangular.
-    fn from(source: &[R]) -> Map<T> {
-        let height = source.len();
-        if height == 0 {
-            return Map {
-                tiles: Vec::new(),
-                width: 0,
-                height: 0,
-            };
+    fn neg(self) -> Self {
+        self * -1
+    }
+}
+
+/// The smallest axis-aligned box containing some points, inclusive of both corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub struct BoundingBox<const D: usize> {
+    pub min: PointN<D>,
+    pub max: PointN<D>,
+}
+
+impl<const D: usize> BoundingBox<D> {
+    /// `None` if there are no points.
+    pub fn from_points<I>(points: I) -> Option<BoundingBox<D>>
+    where
+        I: IntoIterator<Item = PointN<D>>,
+    {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bbox = BoundingBox {
+            min: first,
+            max: first,
+        };
+        for point in points {
+            bbox.include(point);
         }
+        Some(bbox)
+    }
 
-        let width = source[0].as_ref().len();
-        assert!(
-            source
-                .as_ref()
-                .iter()
-                .all(|row| row.as_ref().len() == width),
-            "input must be rectangular"
-        );
+    /// Grow the box if necessary so it contains `point`.
+    pub fn include(&mut self, point: PointN<D>) {
+        for axis in 0..D {
+            self.min[axis] = self.min[axis].min(point[axis]);
+            self.max[axis] = self.max[axis].max(point[axis]);
+        }
+    }
 
-        let mut tiles = Vec::with_capacity(width * height);
-        for row in source.iter() {
-            for tile in row.as_ref().iter() {
-                tiles.push(tile.clone());
-            }
+    pub fn contains(&self, point: PointN<D>) -> bool {
+        (0..D).all(|axis| self.min[axis] <= point[axis] && point[axis] <= self.max[axis])
+    }
+
+    /// This box, with `by` added on every side.
+    pub fn expand(&self, by: i32) -> BoundingBox<D> {
+        BoundingBox {
+            min: self.min - PointN([by; D]),
+            max: self.max + PointN([by; D]),
         }
+    }
 
-        Map {
-            tiles,
-            width,
-            height,
+    /// The number of points along each axis.
+    pub fn size(&self) -> [usize; D] {
+        let mut size = [0; D];
+        for (axis, len) in size.iter_mut().enumerate() {
+            *len = (self.max[axis] - self.min[axis] + 1) as usize;
         }
+        size
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum MapConversionErr<T>
-where
+    /// The number of points in the box.
+    pub fn volume(&self) -> usize {
+        self.size().iter().product()
+    }
+
+    /// Every point in the box, varying the first axis fastest.
+    pub fn points(&self) -> impl Iterator<Item = PointN<D>> {
+        let min = self.min;
+        let size = self.size();
+        (0..self.volume()).map(move |mut idx| {
+            let mut point = min;
+            for axis in 0..D {
+                point[axis] += (idx % size[axis]) as i32;
+                idx /= size[axis];
+            }
+            point
+        })
+    }
+}
+
+/// A direction on a hex grid of pointy-topped hexes, whose rows run east to west.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub enum HexDirection {
+    East,
+    SouthEast,
+    SouthWest,
+    West,
+    NorthWest,
+    NorthEast,
+}
+
+impl HexDirection {
+    /// (dq, dr) in axial coordinates, for which r increases to the south
+    pub fn deltas(self) -> (i32, i32) {
+        use HexDirection::*;
+        match self {
+            East => (1, 0),
+            SouthEast => (0, 1),
+            SouthWest => (-1, 1),
+            West => (-1, 0),
+            NorthWest => (0, -1),
+            NorthEast => (1, -1),
+        }
+    }
+
+    /// Turn 60 degrees clockwise.
+    pub fn turn_right(self) -> HexDirection {
+        use HexDirection::*;
+        match self {
+            East => SouthEast,
+            SouthEast => SouthWest,
+            SouthWest => West,
+            West => NorthWest,
+            NorthWest => NorthEast,
+            NorthEast => East,
+        }
+    }
+
+    /// Turn 60 degrees anticlockwise.
+    pub fn turn_left(self) -> HexDirection {
+        use HexDirection::*;
+        match self {
+            East => NorthEast,
+            NorthEast => NorthWest,
+            NorthWest => West,
+            West => SouthWest,
+            SouthWest => SouthEast,
+            SouthEast => East,
+        }
+    }
+
+    /// Clockwise, starting from east.
+    pub fn iter() -> impl Iterator<Item = HexDirection> {
+        use HexDirection::*;
+        [East, SouthEast, SouthWest, West, NorthWest, NorthEast]
+            .iter()
+            .cloned()
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use HexDirection::*;
+        match s {
+            "e" => Ok(East),
+            "se" => Ok(SouthEast),
+            "sw" => Ok(SouthWest),
+            "w" => Ok(West),
+            "nw" => Ok(NorthWest),
+            "ne" => Ok(NorthEast),
+            unknown => Err(format!("unknown hex direction: {}", unknown)),
+        }
+    }
+}
+
+/// A sequence of hex directions, written without separators: `esenee` is east, southeast,
+/// northeast, east.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HexPath(pub Vec<HexDirection>);
+
+impl HexPath {
+    /// Where following this path from `start` ends up.
+    pub fn follow(&self, start: HexPoint) -> HexPoint {
+        self.0
+            .iter()
+            .fold(start, |point, direction| point + *direction)
+    }
+}
+
+impl FromStr for HexPath {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut directions = Vec::new();
+        let mut rest = s.trim();
+        while !rest.is_empty() {
+            // north and south never stand alone, so always take a second character with them
+            let chars = if rest.starts_with('n') || rest.starts_with('s') {
+                2
+            } else {
+                1
+            };
+            // split after whole characters, never inside one
+            let len = rest
+                .char_indices()
+                .nth(chars)
+                .map_or(rest.len(), |(idx, _)| idx);
+            let (direction, tail) = rest.split_at(len);
+            directions.push(direction.parse()?);
+            rest = tail;
+        }
+        Ok(HexPath(directions))
+    }
+}
+
+/// A hex on a grid of pointy-topped hexes, in axial coordinates.
+///
+/// The implicit third cube coordinate is `s = -q - r`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub struct HexPoint {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl HexPoint {
+    pub const fn new(q: i32, r: i32) -> HexPoint {
+        HexPoint { q, r }
+    }
+
+    /// Panics if `q + r + s != 0`.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> HexPoint {
+        assert_eq!(q + r + s, 0, "cube coordinates must sum to 0");
+        HexPoint { q, r }
+    }
+
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// (q, r, s)
+    pub fn cube(&self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    /// The six adjacent hexes, clockwise from east.
+    pub fn neighbours(&self) -> Vec<HexPoint> {
+        HexDirection::iter().map(|d| *self + d).collect()
+    }
+
+    /// The number of steps between two hexes.
+    pub fn distance(&self, other: HexPoint) -> i32 {
+        let d = *self - other;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    /// Rotate 60 degrees clockwise about the origin.
+    pub fn rotate_right(&self) -> HexPoint {
+        let (q, r, s) = self.cube();
+        HexPoint::from_cube(-r, -s, -q)
+    }
+
+    /// Rotate 60 degrees anticlockwise about the origin.
+    pub fn rotate_left(&self) -> HexPoint {
+        let (q, r, s) = self.cube();
+        HexPoint::from_cube(-s, -q, -r)
+    }
+
+    /// Every hex exactly `radius` steps away, clockwise from the westernmost.
+    pub fn ring(&self, radius: u32) -> Vec<HexPoint> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let radius = radius as i32;
+        let mut out = Vec::with_capacity(6 * radius as usize);
+        let mut hex = *self + HexPoint::from(HexDirection::West) * radius;
+        let mut direction = HexDirection::NorthEast;
+        for _ in 0..6 {
+            for _ in 0..radius {
+                out.push(hex);
+                hex = hex + direction;
+            }
+            direction = direction.turn_right();
+        }
+        out
+    }
+
+    /// Every hex within `radius` steps, ring by ring outwards from this one.
+    pub fn spiral(&self, radius: u32) -> Vec<HexPoint> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+
+    /// This hex's position on a square grid of twice the width, with y up, as used for
+    /// rendering: each step east is two columns, and each row is offset by one column from the
+    /// next.
+    pub fn doubled(&self) -> Point {
+        Point::new(2 * self.q + self.r, -self.r)
+    }
+}
+
+impl From<HexDirection> for HexPoint {
+    fn from(direction: HexDirection) -> Self {
+        let (q, r) = direction.deltas();
+        HexPoint { q, r }
+    }
+}
+
+impl Add for HexPoint {
+    type Output = HexPoint;
+
+    fn add(self, other: HexPoint) -> HexPoint {
+        HexPoint {
+            q: self.q + other.q,
+            r: self.r + other.r,
+        }
+    }
+}
+
+impl Add<HexDirection> for HexPoint {
+    type Output = HexPoint;
+
+    fn add(self, direction: HexDirection) -> HexPoint {
+        self + HexPoint::from(direction)
+    }
+}
+
+impl Sub for HexPoint {
+    type Output = HexPoint;
+
+    fn sub(self, other: HexPoint) -> HexPoint {
+        HexPoint {
+            q: self.q - other.q,
+            r: self.r - other.r,
+        }
+    }
+}
+
+impl Mul<i32> for HexPoint {
+    type Output = HexPoint;
+
+    fn mul(self, other: i32) -> HexPoint {
+        HexPoint {
+            q: self.q * other,
+            r: self.r * other,
+        }
+    }
+}
+
+/// Which way the y axis of a [`Map`] points.
+///
+/// Either way, the origin is at the left and [`Direction::Up`] means `+y`; only which row is
+/// numbered 0 differs, so on a [`YAxis::Down`] map `Direction::Up` points down the page. Methods
+/// named `_on_page`, such as [`Map::step_on_page`], take directions as seen in the picture
+/// instead. Text is always written and read top row first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub enum YAxis {
+    /// The origin is in the lower left, and `+y` is up the page.
+    #[default]
+    Up,
+    /// The origin is in the upper left, and `+y` is down the page, as puzzles describe grids.
+    Down,
+}
+
+impl YAxis {
+    fn text(self) -> &'static str {
+        match self {
+            YAxis::Up => "y-up",
+            YAxis::Down => "y-down",
+        }
+    }
+}
+
+/// A Map keeps track of a tile grid.
+///
+/// It is based on immutable data structures, so is cheap to clone.
+///
+/// By default its coordinate system assumes that the origin is in the lower left,
+/// for compatibility with Direction; see [`YAxis`] for the alternative.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "geometry-serde",
+    derive(Serialize, Deserialize),
+    serde(try_from = "MapData<T>")
+)]
+pub struct Map<T: Clone> {
+    /// Bottom row first, whatever the y axis.
+    tiles: Vec<T>,
+    width: usize,
+    height: usize,
+    #[cfg_attr(feature = "geometry-serde", serde(default))]
+    y_axis: YAxis,
+}
+
+/// A map as deserialized, before checking that its tiles fill its dimensions.
+#[cfg(feature = "geometry-serde")]
+#[derive(Deserialize)]
+struct MapData<T> {
+    tiles: Vec<T>,
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    y_axis: YAxis,
+}
+
+#[cfg(feature = "geometry-serde")]
+impl<T: Clone> TryFrom<MapData<T>> for Map<T> {
+    type Error = String;
+
+    fn try_from(data: MapData<T>) -> Result<Self, Self::Error> {
+        if data.tiles.len() != data.width * data.height {
+            return Err(format!(
+                "{} tiles cannot fill a {}x{} map",
+                data.tiles.len(),
+                data.width,
+                data.height
+            ));
+        }
+        Ok(Map {
+            tiles: data.tiles,
+            width: data.width,
+            height: data.height,
+            y_axis: data.y_axis,
+        })
+    }
+}
+
+impl<T: Clone + Default> Map<T> {
+    pub fn new(width: usize, height: usize) -> Map<T> {
+        Map {
+            tiles: vec![T::default(); width * height].into(),
+            width,
+            height,
+            y_axis: YAxis::Up,
+        }
+    }
+}
+
+impl<T: Clone> Map<T> {
+    /// Every tile, row by row from `y = 0`, in the same order as
+    /// [`for_each_point`](Self::for_each_point).
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.height).flat_map(move |y| {
+            let row = self.stored_row(y) * self.width;
+            self.tiles[row..row + self.width].iter()
+        })
+    }
+}
+
+impl<T: Clone + std::hash::Hash> std::hash::Hash for Map<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.tiles.hash(state);
+        self.width.hash(state);
+        self.height.hash(state);
+        self.y_axis.hash(state);
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for Map<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.y_axis == other.y_axis
+            && self.tiles == other.tiles
+    }
+}
+
+impl<T: Clone + Eq> Eq for Map<T> {}
+
+impl<T, R> From<&[R]> for Map<T>
+where
+    T: Clone,
+    R: AsRef<[T]>,
+{
+    /// Convert an input 2d array into a map.
+    ///
+    /// Note that the input array must already be arranged with the y axis
+    /// as the outer array and the orientation such that source[0][0] is the
+    /// lower left corner of the map.
+    ///
+    /// Panics if the input array is not rectangular.
+    fn from(source: &[R]) -> Map<T> {
+        let height = source.len();
+        if height == 0 {
+            return Map {
+                tiles: Vec::new(),
+                width: 0,
+                height: 0,
+                y_axis: YAxis::Up,
+            };
+        }
+
+        let width = source[0].as_ref().len();
+        assert!(
+            source
+                .as_ref()
+                .iter()
+                .all(|row| row.as_ref().len() == width),
+            "input must be rectangular"
+        );
+
+        let mut tiles = Vec::with_capacity(width * height);
+        for row in source.iter() {
+            for tile in row.as_ref().iter() {
+                tiles.push(tile.clone());
+            }
+        }
+
+        Map {
+            tiles,
+            width,
+            height,
+            y_axis: YAxis::Up,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapConversionErr<T>
+where
     T: TryFrom<char>,
     <T as TryFrom<char>>::Error: std::fmt::Debug + Clone + PartialEq + Eq,
 {
     TileConversion(<T as TryFrom<char>>::Error),
     NotRectangular,
+    /// The header of the [text format](Map::to_text) is missing or malformed.
+    BadHeader(String),
+    /// The rows of the text format don't match its header.
+    WrongSize {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
 }
 
 impl<T> fmt::Display for MapConversionErr<T>
@@ -445,6 +1386,12 @@ where
         match self {
             Self::TileConversion(err) => write!(f, "{:?}", err),
             Self::NotRectangular => write!(f, "maps must be rectangular"),
+            Self::BadHeader(header) => write!(f, "bad map header: {:?}", header),
+            Self::WrongSize { expected, found } => write!(
+                f,
+                "map header says {}x{} but found {}x{}",
+                expected.0, expected.1, found.0, found.1
+            ),
         }
     }
 }
@@ -500,6 +1447,59 @@ where
 
         Ok(Map::from(arr.as_slice()))
     }
+
+    /// Parse the lossless text format written by [`to_text`](Self::to_text).
+    pub fn from_text(text: &str) -> Result<Self, MapConversionErr<T>> {
+        let mut lines = text.lines();
+        let header = lines.next().unwrap_or_default();
+        let bad_header = || MapConversionErr::BadHeader(header.to_string());
+        let (width, height, y_axis) = match header.split(' ').collect::<Vec<_>>().as_slice() {
+            [MAP_TEXT_TAG, size, y_axis] => {
+                let (width, height) = size.split_once('x').ok_or_else(bad_header)?;
+                let y_axis = [YAxis::Up, YAxis::Down]
+                    .iter()
+                    .copied()
+                    .find(|known| known.text() == *y_axis)
+                    .ok_or_else(bad_header)?;
+                (
+                    width.parse::<usize>().map_err(|_| bad_header())?,
+                    height.parse::<usize>().map_err(|_| bad_header())?,
+                    y_axis,
+                )
+            }
+            _ => return Err(bad_header()),
+        };
+
+        let mut tiles = Vec::with_capacity(width * height);
+        let mut rows = 0;
+        for line in lines {
+            let mut row_width = 0;
+            for ch in line.chars() {
+                tiles.push(T::try_from(ch).map_err(MapConversionErr::TileConversion)?);
+                row_width += 1;
+            }
+            if row_width != width {
+                return Err(MapConversionErr::WrongSize {
+                    expected: (width, height),
+                    found: (row_width, rows + 1),
+                });
+            }
+            rows += 1;
+        }
+        if rows != height {
+            return Err(MapConversionErr::WrongSize {
+                expected: (width, height),
+                found: (width, rows),
+            });
+        }
+
+        // rows are written top first, but stored bottom first
+        let rows: Vec<_> = tiles.chunks(width.max(1)).rev().collect();
+        let mut map = Map::from(rows.as_slice());
+        map.width = width;
+        map.height = height;
+        Ok(map.with_y_axis(y_axis))
+    }
 }
 
 impl<T> TryFrom<&str> for Map<T>
@@ -545,11 +1545,27 @@ where
     }
 }
 
+impl<T: Clone> Map<T> {
+    /// The row of `tiles` holding row `y` of this map's coordinates.
+    fn stored_row(&self, y: usize) -> usize {
+        match self.y_axis {
+            YAxis::Up => y,
+            YAxis::Down => self.height - 1 - y,
+        }
+    }
+
+    /// The tile `x` across and `y` up from the lower left, whatever the y axis.
+    fn stored(&self, (x, y): (usize, usize)) -> &T {
+        &self.tiles[x + y * self.width]
+    }
+}
+
 impl<T: Clone> Index<(usize, usize)> for Map<T> {
     type Output = T;
 
     fn index(&self, (x, y): (usize, usize)) -> &T {
-        self.tiles.index(x + (y * self.width))
+        debug_assert!(y < self.height, "y out of bounds");
+        self.tiles.index(x + (self.stored_row(y) * self.width))
     }
 }
 
@@ -568,7 +1584,9 @@ impl<T: Clone> Index<Point> for Map<T> {
 
 impl<T: Clone> IndexMut<(usize, usize)> for Map<T> {
     fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
-        self.tiles.index_mut(x + (y * self.width))
+        debug_assert!(y < self.height, "y out of bounds");
+        let row = self.stored_row(y);
+        self.tiles.index_mut(x + (row * self.width))
     }
 }
 
@@ -583,11 +1601,31 @@ impl<T: Clone> IndexMut<Point> for Map<T> {
     }
 }
 
+const MAP_TEXT_TAG: &str = "map";
+
+impl<T: Clone + Into<char>> Map<T> {
+    /// Write this map in a text format which can be parsed back exactly with
+    /// [`from_text`](Self::from_text), even when it is empty.
+    ///
+    /// A header line gives the dimensions and [`YAxis`], as `map 10x3 y-up`; the rows follow,
+    /// top first, as for `Display`. Tiles must convert to and from `char` losslessly.
+    pub fn to_text(&self) -> String {
+        format!(
+            "{} {}x{} {}\n{}",
+            MAP_TEXT_TAG,
+            self.width,
+            self.height,
+            self.y_axis.text(),
+            self
+        )
+    }
+}
+
 impl<T: Clone + Into<char>> fmt::Display for Map<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for y in (0..self.height).rev() {
             for x in 0..self.width {
-                write!(f, "{}", self[(x, y)].clone().into())?;
+                write!(f, "{}", self.stored((x, y)).clone().into())?;
             }
             write!(f, "\n")?;
         }
@@ -600,14 +1638,19 @@ impl<T: Clone> Map<T> {
     where
         F: FnMut(&T),
     {
-        self.tiles.iter().for_each(visit);
+        self.iter().for_each(visit);
     }
 
-    pub fn for_each_mut<F>(&mut self, update: F)
+    pub fn for_each_mut<F>(&mut self, mut update: F)
     where
         F: FnMut(&mut T),
     {
-        self.tiles.iter_mut().for_each(update);
+        for y in 0..self.height {
+            let row = self.stored_row(y) * self.width;
+            self.tiles[row..row + self.width]
+                .iter_mut()
+                .for_each(&mut update);
+        }
     }
 
     pub fn for_each_point<F>(&self, mut visit: F)
@@ -641,28 +1684,890 @@ impl<T: Clone> Map<T> {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-/// Can a visitor move through this map tile?
-pub enum Traversable {
-    /// Obstructed tiles cannot be moved into.
-    Obstructed,
-    /// Free tiles can be moved through.
-    Free,
-    /// Halt tiles can be moved into, but not past.
-    Halt,
-}
+impl<T: Clone> Map<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
 
-/// Safe fast value-to-value conversion which consumes the input value and references some context.
-///
-/// This trait should be implemented in preference to [`ContextInto`][ContextInto].
-pub trait ContextFrom<T> {
-    type Context;
+    pub fn height(&self) -> usize {
+        self.height
+    }
 
-    fn ctx_from(t: T, context: &Self::Context) -> Self;
-}
+    pub fn y_axis(&self) -> YAxis {
+        self.y_axis
+    }
 
-impl<A, B> ContextFrom<A> for B
-where
+    /// The same picture, with points numbered according to `y_axis`.
+    pub fn with_y_axis(mut self, y_axis: YAxis) -> Map<T> {
+        self.y_axis = y_axis;
+        self
+    }
+
+    /// Where the tile at `point` lies in this map's picture if its points were numbered
+    /// according to `y_axis` instead.
+    pub fn convert_point(&self, point: Point, y_axis: YAxis) -> Point {
+        if y_axis == self.y_axis {
+            point
+        } else {
+            Point::new(point.x, self.height as i32 - 1 - point.y)
+        }
+    }
+
+    /// The neighbour of `point` which is one step in `direction` as seen on the page, rather
+    /// than along the axes: on a [`YAxis::Down`] map, going up the page is `-y`.
+    pub fn step_on_page(&self, point: Point, direction: Direction) -> Point {
+        match (self.y_axis, direction) {
+            (YAxis::Down, Direction::Up) => point + Direction::Down,
+            (YAxis::Down, Direction::Down) => point + Direction::Up,
+            _ => point + direction,
+        }
+    }
+
+    /// View this map in another orientation without copying it.
+    pub fn oriented(&self, orientation: Orientation) -> Oriented<'_, T> {
+        let (width, height) = if orientation.quarter_turns.is_multiple_of(2) {
+            (self.width, self.height)
+        } else {
+            (self.height, self.width)
+        };
+        Oriented {
+            map: self,
+            orientation,
+            width,
+            height,
+        }
+    }
+
+    /// Views of all eight orientations of this map, in the order of [`Orientation::all`].
+    pub fn orientations(&self) -> impl Iterator<Item = Oriented<'_, T>> {
+        Orientation::all().map(move |orientation| self.oriented(orientation))
+    }
+
+    /// Rotate clockwise by some number of quarter turns.
+    pub fn rotate(&self, quarter_turns: usize) -> Map<T> {
+        self.oriented(Orientation::new(quarter_turns, false))
+            .to_map()
+    }
+
+    /// Mirror left to right.
+    pub fn flip_horizontal(&self) -> Map<T> {
+        self.oriented(Orientation::new(0, true)).to_map()
+    }
+
+    /// Mirror top to bottom.
+    pub fn flip_vertical(&self) -> Map<T> {
+        self.oriented(Orientation::new(2, true)).to_map()
+    }
+
+    /// Mirror about the diagonal through the origin, swapping x and y.
+    pub fn transpose(&self) -> Map<T> {
+        self.oriented(Orientation::new(1, true)).to_map()
+    }
+
+    /// Copy the `width` by `height` rectangle whose corner nearest the origin is `origin`.
+    ///
+    /// Panics if the rectangle is not entirely within this map.
+    pub fn sub_map(&self, origin: Point, width: usize, height: usize) -> Map<T> {
+        assert!(
+            self.in_bounds(origin)
+                && origin.x as usize + width <= self.width
+                && origin.y as usize + height <= self.height,
+            "sub-map must be within the map"
+        );
+        let x0 = origin.x as usize;
+        let y0 = match self.y_axis {
+            YAxis::Up => origin.y as usize,
+            YAxis::Down => self.height - height - origin.y as usize,
+        };
+        let mut tiles = Vec::with_capacity(width * height);
+        for y in y0..y0 + height {
+            tiles.extend_from_slice(&self.tiles[x0 + y * self.width..x0 + width + y * self.width]);
+        }
+        Map {
+            tiles,
+            width,
+            height,
+            y_axis: self.y_axis,
+        }
+    }
+
+    /// Copy this map without the outermost `n` tiles on every side.
+    ///
+    /// Panics if the map is not larger than `2n` in both dimensions.
+    pub fn strip_border(&self, n: usize) -> Map<T> {
+        assert!(
+            self.width > 2 * n && self.height > 2 * n,
+            "map too small to strip its border"
+        );
+        self.sub_map(
+            Point::new(n as i32, n as i32),
+            self.width - 2 * n,
+            self.height - 2 * n,
+        )
+    }
+
+    /// The tiles along one side of the map; see [`Oriented::edge`].
+    pub fn edge(&self, side: Direction) -> Vec<T> {
+        self.oriented(Orientation::default()).edge(side)
+    }
+
+    /// The tiles along one side of the map as seen on the page; see [`Oriented::edge_on_page`].
+    pub fn edge_on_page(&self, side: Direction) -> Vec<T> {
+        self.oriented(Orientation::default()).edge_on_page(side)
+    }
+}
+
+/// One of the eight symmetries of a rectangle: an optional left-to-right flip, followed by some
+/// clockwise quarter turns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub struct Orientation {
+    pub quarter_turns: u8,
+    pub flipped: bool,
+}
+
+impl Orientation {
+    pub fn new(quarter_turns: usize, flipped: bool) -> Orientation {
+        Orientation {
+            quarter_turns: (quarter_turns % 4) as u8,
+            flipped,
+        }
+    }
+
+    /// The four rotations, then the four rotations of the flip.
+    pub fn all() -> impl Iterator<Item = Orientation> {
+        [false, true]
+            .iter()
+            .flat_map(|flipped| (0..4).map(move |turns| Orientation::new(turns, *flipped)))
+    }
+}
+
+/// A borrowed view of a [`Map`] in some [`Orientation`].
+///
+/// Its coordinates follow the same conventions as the map's own, [`YAxis`] included.
+#[derive(Clone, Copy)]
+pub struct Oriented<'a, T: Clone> {
+    map: &'a Map<T>,
+    orientation: Orientation,
+    width: usize,
+    height: usize,
+}
+
+impl<'a, T: Clone> Oriented<'a, T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Where a point of this view lies on the underlying map.
+    fn source(&self, (mut x, mut y): (usize, usize)) -> (usize, usize) {
+        // undo each quarter turn, most recent first; the image before an odd number of turns
+        // is as wide as the map, and before an even number as tall
+        for turns_before in (0..self.orientation.quarter_turns).rev() {
+            let width_before = if turns_before % 2 == 0 {
+                self.map.width
+            } else {
+                self.map.height
+            };
+            let (rx, ry) = (x, y);
+            x = width_before - 1 - ry;
+            y = rx;
+        }
+        if self.orientation.flipped {
+            x = self.map.width - 1 - x;
+        }
+        (x, y)
+    }
+
+    /// The tile `x` across and `y` up from the lower left of the view, whatever the y axis.
+    fn visual(&self, point: (usize, usize)) -> &'a T {
+        self.map.stored(self.source(point))
+    }
+
+    pub fn in_bounds(&self, point: Point) -> bool {
+        point.x >= 0
+            && point.y >= 0
+            && (point.x as usize) < self.width
+            && (point.y as usize) < self.height
+    }
+
+    pub fn for_each_point<F>(&self, mut visit: F)
+    where
+        F: FnMut(&T, Point),
+    {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                visit(&self[(x, y)], (x, y).into());
+            }
+        }
+    }
+
+    /// The tiles along one side of the view, the side a step in `side` from any tile leads off.
+    ///
+    /// Each edge runs in order of increasing coordinate: the `Up` and `Down` edges by x, and the
+    /// `Left` and `Right` edges by y. On a [`YAxis::Down`] view, the `Up` edge is the bottom row.
+    pub fn edge(&self, side: Direction) -> Vec<T> {
+        Self::edge_points(self.width, self.height, side)
+            .into_iter()
+            .map(|point| self[point].clone())
+            .collect()
+    }
+
+    /// The tiles along one side of the view as seen on the page, whatever the y axis.
+    ///
+    /// The top and bottom edges run left to right, and the left and right edges bottom to top.
+    pub fn edge_on_page(&self, side: Direction) -> Vec<T> {
+        Self::edge_points(self.width, self.height, side)
+            .into_iter()
+            .map(|point| self.visual(point).clone())
+            .collect()
+    }
+
+    /// The points along one side of a `width` by `height` rectangle, as for [`Self::edge`].
+    fn edge_points(width: usize, height: usize, side: Direction) -> Vec<(usize, usize)> {
+        match side {
+            Direction::Up => (0..width).map(|x| (x, height - 1)).collect(),
+            Direction::Down => (0..width).map(|x| (x, 0)).collect(),
+            Direction::Left => (0..height).map(|y| (0, y)).collect(),
+            Direction::Right => (0..height).map(|y| (width - 1, y)).collect(),
+        }
+    }
+
+    /// Copy this view into a new map.
+    pub fn to_map(&self) -> Map<T> {
+        let mut tiles = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                tiles.push(self.visual((x, y)).clone());
+            }
+        }
+        Map {
+            tiles,
+            width: self.width,
+            height: self.height,
+            y_axis: self.map.y_axis,
+        }
+    }
+}
+
+impl<'a, T: Clone> Index<(usize, usize)> for Oriented<'a, T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        debug_assert!(x < self.width && y < self.height);
+        let y = match self.map.y_axis {
+            YAxis::Up => y,
+            YAxis::Down => self.height - 1 - y,
+        };
+        self.visual((x, y))
+    }
+}
+
+impl<'a, T: Clone> Index<Point> for Oriented<'a, T> {
+    type Output = T;
+
+    /// Panics if point.x or point.y < 0
+    fn index(&self, point: Point) -> &T {
+        assert!(
+            point.x >= 0 && point.y >= 0,
+            "point must be in the positive quadrant"
+        );
+        self.index((point.x as usize, point.y as usize))
+    }
+}
+
+/// How a [`MapView`] treats points beyond the map along one axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub enum Edge {
+    /// There is nothing beyond the map.
+    Bounded,
+    /// The map repeats forever.
+    Wrap,
+    /// The tiles along the edge extend forever.
+    Clamp,
+}
+
+impl Edge {
+    fn resolve(self, coord: i32, len: usize) -> Option<i32> {
+        use std::convert::TryInto;
+        let len: i32 = len.try_into().unwrap_or(i32::MAX);
+        if len == 0 {
+            return None;
+        }
+        match self {
+            Edge::Bounded => Some(coord).filter(|coord| (0..len).contains(coord)),
+            Edge::Wrap => Some(coord.rem_euclid(len)),
+            Edge::Clamp => Some(coord.clamp(0, len - 1)),
+        }
+    }
+}
+
+/// How a [`MapView`] treats points beyond the map along each axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub struct Edges {
+    pub x: Edge,
+    pub y: Edge,
+}
+
+impl Edges {
+    pub const BOUNDED: Edges = Edges::new(Edge::Bounded, Edge::Bounded);
+    /// The map repeats forever to the left and right.
+    pub const WRAP_X: Edges = Edges::new(Edge::Wrap, Edge::Bounded);
+    /// The map repeats forever above and below.
+    pub const WRAP_Y: Edges = Edges::new(Edge::Bounded, Edge::Wrap);
+    /// The map repeats forever in every direction.
+    pub const TORUS: Edges = Edges::new(Edge::Wrap, Edge::Wrap);
+    pub const CLAMP: Edges = Edges::new(Edge::Clamp, Edge::Clamp);
+
+    pub const fn new(x: Edge, y: Edge) -> Edges {
+        Edges { x, y }
+    }
+}
+
+/// A view of a [`Map`] which extends beyond its bounds according to some [`Edges`].
+///
+/// Points within the map are unchanged.
+pub struct MapView<'a, T: Clone> {
+    map: &'a Map<T>,
+    edges: Edges,
+}
+
+impl<T: Clone> Map<T> {
+    pub fn view(&self, edges: Edges) -> MapView<'_, T> {
+        MapView { map: self, edges }
+    }
+}
+
+impl<'a, T: Clone> MapView<'a, T> {
+    pub fn edges(&self) -> Edges {
+        self.edges
+    }
+
+    /// The point of the underlying map which `point` shows, if any.
+    pub fn resolve(&self, point: Point) -> Option<Point> {
+        Some(Point::new(
+            self.edges.x.resolve(point.x, self.map.width)?,
+            self.edges.y.resolve(point.y, self.map.height)?,
+        ))
+    }
+
+    pub fn get(&self, point: Point) -> Option<&'a T> {
+        let map = self.map;
+        self.resolve(point).map(|point| &map[point])
+    }
+
+    /// Walk from `start` by repeatedly adding `step`, visiting every tile passed.
+    ///
+    /// `start` itself is not visited. The walk ends on leaving the map along a bounded axis; a
+    /// walk with no bounded axis to leave by never ends.
+    pub fn ray(&self, start: Point, step: Point) -> Ray<'a, T> {
+        Ray {
+            view: MapView {
+                map: self.map,
+                edges: self.edges,
+            },
+            current: start,
+            step,
+        }
+    }
+}
+
+impl<'a, T: Clone> Index<Point> for MapView<'a, T> {
+    type Output = T;
+
+    /// Panics if `point` lies beyond a bounded edge.
+    fn index(&self, point: Point) -> &T {
+        self.get(point).expect("point must lie within the view")
+    }
+}
+
+/// An iterator of `(point, tile)` along a straight line of a [`MapView`], from [`MapView::ray`].
+///
+/// Points are those of the underlying map.
+pub struct Ray<'a, T: Clone> {
+    view: MapView<'a, T>,
+    current: Point,
+    step: Point,
+}
+
+impl<'a, T: Clone> Iterator for Ray<'a, T> {
+    type Item = (Point, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.current = self.current + self.step;
+        let point = self.view.resolve(self.current)?;
+        Some((point, &self.view.map[point]))
+    }
+}
+
+impl<T: Clone> Map<T> {
+    /// The first point from `from` in `direction` whose tile is opaque, if any; `from` itself is
+    /// not considered.
+    pub fn first_visible<F>(&self, from: Point, direction: Compass, is_opaque: F) -> Option<Point>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.view(Edges::BOUNDED)
+            .ray(from, direction.into())
+            .find(|(_, tile)| is_opaque(tile))
+            .map(|(point, _)| point)
+    }
+
+    /// Find once, for every viewing tile, the first opaque tile visible in each compass
+    /// direction.
+    ///
+    /// If every tile is opaque, this is simply each viewer's neighbours.
+    #[tracing::instrument(level = "debug", skip(self, is_viewer, is_opaque))]
+    pub fn visibility_graph<V, O>(&self, is_viewer: V, is_opaque: O) -> VisibilityGraph
+    where
+        V: Fn(&T) -> bool,
+        O: Fn(&T) -> bool,
+    {
+        let mut visible = Map {
+            tiles: vec![Vec::new(); self.tiles.len()],
+            width: self.width,
+            height: self.height,
+            y_axis: self.y_axis,
+        };
+        self.for_each_point(|tile, point| {
+            if is_viewer(tile) {
+                visible[point] = Compass::iter()
+                    .filter_map(|direction| self.first_visible(point, direction, &is_opaque))
+                    .collect();
+            }
+        });
+        VisibilityGraph { visible }
+    }
+}
+
+/// Which points each point of a map can see, from [`Map::visibility_graph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibilityGraph {
+    visible: Map<Vec<Point>>,
+}
+
+impl VisibilityGraph {
+    /// Empty for points which aren't viewers or are out of bounds.
+    pub fn visible(&self, point: Point) -> &[Point] {
+        if self.visible.in_bounds(point) {
+            &self.visible[point]
+        } else {
+            &[]
+        }
+    }
+}
+
+/// A small map to search for within a larger one, in which `None` cells match anything.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Pattern<T: Clone> {
+    cells: Map<Option<T>>,
+}
+
+/// Where a [`Pattern`] was found by [`Map::find_pattern`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub struct PatternMatch {
+    /// The map position of the oriented pattern's corner nearest the map's origin: its lower
+    /// left on a [`YAxis::Up`] map, its upper left on a [`YAxis::Down`] one.
+    pub origin: Point,
+    pub orientation: Orientation,
+    /// The y axis of the map searched, which `origin` and covered cells follow.
+    pub y_axis: YAxis,
+}
+
+impl<T: Clone> Pattern<T> {
+    pub fn new(cells: Map<Option<T>>) -> Pattern<T> {
+        Pattern { cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height
+    }
+
+    /// The cells which must match, relative to the lower left corner of the oriented pattern.
+    fn fixed_cells(&self, orientation: Orientation) -> Vec<(Point, T)> {
+        let oriented = self.cells.oriented(orientation);
+        let mut fixed = Vec::new();
+        for y in 0..oriented.height() {
+            for x in 0..oriented.width() {
+                if let Some(cell) = oriented.visual((x, y)) {
+                    fixed.push((Point::from((x, y)), cell.clone()));
+                }
+            }
+        }
+        fixed
+    }
+
+    /// The map positions of the cells which must match, for a particular match.
+    pub fn covered(&self, found: &PatternMatch) -> impl Iterator<Item = Point> {
+        let found = *found;
+        let height = self.cells.oriented(found.orientation).height() as i32;
+        self.fixed_cells(found.orientation)
+            .into_iter()
+            .map(move |(point, _)| match found.y_axis {
+                YAxis::Up => found.origin + point,
+                YAxis::Down => found.origin + Point::new(point.x, height - 1 - point.y),
+            })
+    }
+
+    /// The map positions covered by any of several matches, each counted once even where
+    /// matches overlap.
+    pub fn covered_cells<'a, I>(&self, matches: I) -> HashSet<Point>
+    where
+        I: IntoIterator<Item = &'a PatternMatch>,
+    {
+        matches
+            .into_iter()
+            .flat_map(|found| self.covered(found))
+            .collect()
+    }
+}
+
+impl<T> Pattern<T>
+where
+    T: Clone + TryFrom<char>,
+    <T as TryFrom<char>>::Error: std::fmt::Debug + Clone + PartialEq + Eq,
+{
+    /// Parse a pattern in natural graphical order, as for a map, treating `wildcard` as "don't
+    /// care". Lines shorter than the longest are padded with wildcards.
+    pub fn parse(input: &str, wildcard: char) -> Result<Pattern<T>, MapConversionErr<T>> {
+        let mut rows = Vec::new();
+        for line in input.lines().filter(|line| !line.is_empty()) {
+            let mut row = Vec::with_capacity(line.len());
+            for ch in line.chars() {
+                row.push(if ch == wildcard {
+                    None
+                } else {
+                    Some(T::try_from(ch).map_err(MapConversionErr::TileConversion)?)
+                });
+            }
+            rows.push(row);
+        }
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or_default();
+        for row in rows.iter_mut() {
+            row.resize(width, None);
+        }
+
+        // shift the origin
+        rows.reverse();
+
+        Ok(Pattern::new(Map::from(rows.as_slice())))
+    }
+}
+
+impl<T: Clone + PartialEq> Map<T> {
+    /// Find every placement of `pattern`, in any of `orientations`, at which all its fixed cells
+    /// equal the tiles beneath them.
+    ///
+    /// Orientations in which a symmetric pattern looks the same as an earlier one are skipped,
+    /// so each placement is reported once.
+    pub fn find_pattern<I>(&self, pattern: &Pattern<T>, orientations: I) -> Vec<PatternMatch>
+    where
+        I: IntoIterator<Item = Orientation>,
+    {
+        let mut seen = Vec::new();
+        let mut matches = Vec::new();
+        for orientation in orientations {
+            let fixed = pattern.fixed_cells(orientation);
+            if seen.contains(&fixed) {
+                continue;
+            }
+            let oriented = pattern.cells.oriented(orientation);
+            if oriented.width() <= self.width && oriented.height() <= self.height {
+                for y in 0..=self.height - oriented.height() {
+                    for x in 0..=self.width - oriented.width() {
+                        let corner = Point::from((x, y));
+                        if fixed.iter().all(|(point, cell)| {
+                            let point = corner + *point;
+                            self.stored((point.x as usize, point.y as usize)) == cell
+                        }) {
+                            let origin = match self.y_axis {
+                                YAxis::Up => corner,
+                                YAxis::Down => {
+                                    Point::from((x, self.height - oriented.height() - y))
+                                }
+                            };
+                            matches.push(PatternMatch {
+                                origin,
+                                orientation,
+                                y_axis: self.y_axis,
+                            });
+                        }
+                    }
+                }
+            }
+            seen.push(fixed);
+        }
+        matches
+    }
+
+    /// Overwrite every tile covered by any of `matches` with `mark`.
+    pub fn mark_pattern(&mut self, pattern: &Pattern<T>, matches: &[PatternMatch], mark: T) {
+        for found in matches {
+            for point in pattern.covered(found) {
+                let point = self.convert_point(point, found.y_axis);
+                self[point] = mark.clone();
+            }
+        }
+    }
+}
+
+/// A point type which can key a [`SparseMap`].
+pub trait GridPoint: Copy + Eq + std::hash::Hash {
+    /// Every point adjacent to this one, diagonals included.
+    fn neighbours(&self) -> Vec<Self>;
+
+    /// The point whose every coordinate is the lesser of the two points'.
+    fn min_coords(&self, other: &Self) -> Self;
+
+    /// The point whose every coordinate is the greater of the two points'.
+    fn max_coords(&self, other: &Self) -> Self;
+}
+
+impl GridPoint for Point {
+    fn neighbours(&self) -> Vec<Point> {
+        PointN::from(*self).neighbours().map(Point::from).collect()
+    }
+
+    fn min_coords(&self, other: &Point) -> Point {
+        Point::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    fn max_coords(&self, other: &Point) -> Point {
+        Point::new(self.x.max(other.x), self.y.max(other.y))
+    }
+}
+
+impl<const D: usize> GridPoint for PointN<D> {
+    fn neighbours(&self) -> Vec<PointN<D>> {
+        PointN::neighbours(self).collect()
+    }
+
+    fn min_coords(&self, other: &PointN<D>) -> PointN<D> {
+        let mut point = *self;
+        for axis in 0..D {
+            point[axis] = point[axis].min(other[axis]);
+        }
+        point
+    }
+
+    fn max_coords(&self, other: &PointN<D>) -> PointN<D> {
+        let mut point = *self;
+        for axis in 0..D {
+            point[axis] = point[axis].max(other[axis]);
+        }
+        point
+    }
+}
+
+impl GridPoint for HexPoint {
+    fn neighbours(&self) -> Vec<HexPoint> {
+        HexPoint::neighbours(self)
+    }
+
+    /// Compares axial coordinates.
+    fn min_coords(&self, other: &HexPoint) -> HexPoint {
+        HexPoint::new(self.q.min(other.q), self.r.min(other.r))
+    }
+
+    /// Compares axial coordinates.
+    fn max_coords(&self, other: &HexPoint) -> HexPoint {
+        HexPoint::new(self.q.max(other.q), self.r.max(other.r))
+    }
+}
+
+/// A SparseMap keeps track of the occupied tiles of an unbounded grid.
+///
+/// Unlike [`Map`], it has no fixed size: it grows to fit whatever is inserted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMap<P: GridPoint, T> {
+    tiles: HashMap<P, T>,
+}
+
+impl<P: GridPoint, T> Default for SparseMap<P, T> {
+    fn default() -> Self {
+        SparseMap {
+            tiles: HashMap::new(),
+        }
+    }
+}
+
+impl<P: GridPoint, T> SparseMap<P, T> {
+    pub fn new() -> SparseMap<P, T> {
+        SparseMap::default()
+    }
+
+    /// The number of occupied tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    pub fn contains(&self, point: &P) -> bool {
+        self.tiles.contains_key(point)
+    }
+
+    pub fn get(&self, point: &P) -> Option<&T> {
+        self.tiles.get(point)
+    }
+
+    pub fn get_mut(&mut self, point: &P) -> Option<&mut T> {
+        self.tiles.get_mut(point)
+    }
+
+    /// Occupy a tile, returning its previous contents.
+    pub fn insert(&mut self, point: P, tile: T) -> Option<T> {
+        self.tiles.insert(point, tile)
+    }
+
+    /// Vacate a tile, returning its previous contents.
+    pub fn remove(&mut self, point: &P) -> Option<T> {
+        self.tiles.remove(point)
+    }
+
+    /// The occupied tiles, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&P, &T)> {
+        self.tiles.iter()
+    }
+
+    /// The occupied points, in no particular order.
+    pub fn points(&self) -> impl Iterator<Item = &P> {
+        self.tiles.keys()
+    }
+
+    /// The least and greatest corners of the smallest box containing every occupied tile, or
+    /// `None` if the map is empty.
+    pub fn bounds(&self) -> Option<(P, P)> {
+        let mut points = self.tiles.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), point| {
+            (min.min_coords(point), max.max_coords(point))
+        }))
+    }
+
+    /// The occupied tiles adjacent to a point.
+    pub fn neighbours(&self, point: &P) -> impl Iterator<Item = (P, &T)> {
+        point
+            .neighbours()
+            .into_iter()
+            .filter_map(move |neighbour| Some((neighbour, self.tiles.get(&neighbour)?)))
+    }
+}
+
+impl<T: Clone> SparseMap<Point, T> {
+    /// Copy the tiles of a dense map which satisfy `occupied`.
+    pub fn from_map<F>(map: &Map<T>, occupied: F) -> SparseMap<Point, T>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut sparse = SparseMap::new();
+        map.for_each_point(|tile, point| {
+            if occupied(tile) {
+                sparse.insert(point, tile.clone());
+            }
+        });
+        sparse
+    }
+
+    /// Copy the occupied region into a dense map, filling vacant tiles with `vacant`.
+    ///
+    /// Also returns the point which became the dense map's origin, so that `point - origin`
+    /// indexes the dense map. Returns `None` if the map is empty.
+    pub fn to_map(&self, vacant: T) -> Option<(Map<T>, Point)> {
+        let (min, max) = self.bounds()?;
+        let width = (max.x - min.x + 1) as usize;
+        let height = (max.y - min.y + 1) as usize;
+        let mut map = Map {
+            tiles: vec![vacant; width * height],
+            width,
+            height,
+            y_axis: YAxis::Up,
+        };
+        for (point, tile) in self.iter() {
+            map[*point - min] = tile.clone();
+        }
+        Some((map, min))
+    }
+}
+
+impl<P: GridPoint, T> std::iter::FromIterator<(P, T)> for SparseMap<P, T> {
+    fn from_iter<I: IntoIterator<Item = (P, T)>>(iter: I) -> Self {
+        SparseMap {
+            tiles: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<P: GridPoint, T> Extend<(P, T)> for SparseMap<P, T> {
+    fn extend<I: IntoIterator<Item = (P, T)>>(&mut self, iter: I) {
+        self.tiles.extend(iter)
+    }
+}
+
+impl<P: GridPoint, T> Index<P> for SparseMap<P, T> {
+    type Output = T;
+
+    /// Panics if the tile is vacant
+    fn index(&self, point: P) -> &T {
+        &self.tiles[&point]
+    }
+}
+
+/// Render the occupied region, top row first, with `.` for vacant tiles.
+impl<T: Clone + Into<char>> fmt::Display for SparseMap<Point, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((min, max)) = self.bounds() {
+            for y in (min.y..=max.y).rev() {
+                for x in min.x..=max.x {
+                    let tile = self.get(&Point::new(x, y)).cloned();
+                    write!(f, "{}", tile.map_or('.', Into::into))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// Can a visitor move through this map tile?
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub enum Traversable {
+    /// Obstructed tiles cannot be moved into.
+    Obstructed,
+    /// Free tiles can be moved through.
+    Free,
+    /// Halt tiles can be moved into, but not past.
+    Halt,
+}
+
+/// Safe fast value-to-value conversion which consumes the input value and references some context.
+///
+/// This trait should be implemented in preference to [`ContextInto`][ContextInto].
+pub trait ContextFrom<T> {
+    type Context;
+
+    fn ctx_from(t: T, context: &Self::Context) -> Self;
+}
+
+impl<A, B> ContextFrom<A> for B
+where
     B: From<A>,
 {
     type Context = ();
@@ -710,7 +2615,6 @@ where
     }
 
     /// navigate between the given points using A*
-    // https://en.wikipedia.org/wiki/A*_search_algorithm#Pseudocode
     pub fn navigate(&self, from: Point, to: Point) -> Option<Vec<Direction>> {
         self.navigate_ctx(&(), from, to)
     }
@@ -721,6 +2625,7 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
     ///
     /// If the visitor ever returns true, processing halts and no further
     /// points are visited.
+    #[tracing::instrument(level = "debug", skip(self, context, visit))]
     pub fn reachable_from_ctx<F>(
         &self,
         context: &<T as ContextInto<Traversable>>::Context,
@@ -729,11 +2634,15 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
     ) where
         F: FnMut(&T, Point) -> bool,
     {
+        if !self.in_bounds(point) {
+            return;
+        }
+
         let mut visited = bitvec!(0; self.tiles.len());
         let mut queue = VecDeque::new();
         queue.push_back(point);
 
-        let idx = |point: Point| point.x as usize + (point.y as usize * self.width);
+        let idx = |point: Point| self.tile_index(point);
 
         while let Some(point) = queue.pop_front() {
             // we may have scheduled a single point more than once via alternate paths;
@@ -753,7 +2662,7 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
             if traversable == Traversable::Free {
                 for direction in Direction::iter() {
                     let neighbor = point + direction;
-                    if !visited[idx(neighbor)] {
+                    if self.in_bounds(neighbor) && !visited[idx(neighbor)] {
                         queue.push_back(neighbor);
                     }
                 }
@@ -762,46 +2671,266 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
     }
 
     /// navigate between the given points using A*
-    // https://en.wikipedia.org/wiki/A*_search_algorithm#Pseudocode
+    #[tracing::instrument(level = "debug", skip(self, context))]
     pub fn navigate_ctx(
         &self,
         context: &<T as ContextInto<Traversable>>::Context,
         from: Point,
         to: Point,
     ) -> Option<Vec<Direction>> {
-        let mut open_set = BinaryHeap::new();
-        open_set.push(AStarNode {
-            cost: 0,
-            position: from,
-        });
+        let route = self.a_star(
+            from,
+            &[to],
+            |tile, _| match tile.clone().ctx_into(context) {
+                Traversable::Obstructed => None,
+                Traversable::Free | Traversable::Halt => Some(1),
+            },
+            |point| (to - point).manhattan() as u32,
+        )?;
+        debug_assert!(route.directions.len() as i32 >= (to - from).manhattan());
+        Some(route.directions)
+    }
+}
+
+/// Which neighbours of a tile count as touching it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Only orthogonal neighbours touch.
+    Four,
+    /// Orthogonal and diagonal neighbours touch.
+    Eight,
+}
+
+impl Connectivity {
+    /// (dx, dy) of every touching neighbour.
+    pub fn deltas(self) -> &'static [(i32, i32)] {
+        match self {
+            Connectivity::Four => &[(0, 1), (0, -1), (-1, 0), (1, 0)],
+            Connectivity::Eight => &[
+                (0, 1),
+                (0, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (1, 1),
+                (-1, -1),
+                (1, -1),
+            ],
+        }
+    }
+}
 
-        // key: node
-        // value: node preceding it on the cheapest known path from start
-        let mut came_from = HashMap::new();
+/// Identifies a connected component of a [`Map`]; it indexes [`Components::stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "geometry-serde", derive(Serialize, Deserialize))]
+pub struct ComponentId(pub usize);
 
-        // gscore
-        // key: position
-        // value: cost of cheapest path from start to node
-        let mut cheapest_path_cost = HashMap::new();
-        cheapest_path_cost.insert(from, 0_u32);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentStats {
+    pub id: ComponentId,
+    /// The first tile of the component found scanning left to right, bottom to top.
+    pub representative: Point,
+    /// The number of tiles in the component.
+    pub size: usize,
+    pub bounds: BoundingBox<2>,
+    /// Whether any tile of the component lies on the edge of the map.
+    pub touches_border: bool,
+}
+
+/// A map partitioned into connected components.
+#[derive(Debug, Clone)]
+pub struct Components {
+    pub labels: Map<ComponentId>,
+    pub stats: Vec<ComponentStats>,
+}
+
+impl Components {
+    /// The stats of the component containing `point`.
+    pub fn at(&self, point: Point) -> &ComponentStats {
+        &self.stats[self.labels[point].0]
+    }
+}
+
+impl<T: Clone> Map<T> {
+    /// Every point reachable from `start`, in breadth-first order, moving only between
+    /// neighbouring tiles for which `connected(from, to)` holds.
+    ///
+    /// `start` itself comes first, unless it is out of bounds, in which case nothing is.
+    pub fn flood_fill<F>(
+        &self,
+        start: Point,
+        connectivity: Connectivity,
+        connected: F,
+    ) -> Vec<Point>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        let mut visited = bitvec!(0; self.tiles.len());
+        self.fill(start, connectivity, &mut visited, connected)
+    }
+
+    fn fill<F>(
+        &self,
+        start: Point,
+        connectivity: Connectivity,
+        visited: &mut BitVec,
+        mut connected: F,
+    ) -> Vec<Point>
+    where
+        F: FnMut(&T, &T) -> bool,
+    {
+        if !self.in_bounds(start) || visited[self.tile_index(start)] {
+            return Vec::new();
+        }
+        visited.set(self.tile_index(start), true);
+        let mut filled = vec![start];
+        // `filled` doubles as the queue: everything after `next` is still to be expanded
+        let mut next = 0;
+        while let Some(&point) = filled.get(next) {
+            next += 1;
+            for &delta in connectivity.deltas() {
+                let neighbor = point + delta;
+                if !self.in_bounds(neighbor) || visited[self.tile_index(neighbor)] {
+                    continue;
+                }
+                if connected(&self[point], &self[neighbor]) {
+                    visited.set(self.tile_index(neighbor), true);
+                    filled.push(neighbor);
+                }
+            }
+        }
+        filled
+    }
+
+    /// Partition the map into components of touching tiles which share the same `key`.
+    ///
+    /// Component ids are assigned in scan order, left to right and bottom to top.
+    #[tracing::instrument(level = "debug", skip(self, key))]
+    pub fn components<K, F>(&self, connectivity: Connectivity, key: F) -> Components
+    where
+        K: PartialEq,
+        F: Fn(&T) -> K,
+    {
+        let mut labels = Map {
+            tiles: vec![ComponentId::default(); self.tiles.len()],
+            width: self.width,
+            height: self.height,
+            y_axis: self.y_axis,
+        };
+        let mut stats = Vec::new();
+        let mut visited = bitvec!(0; self.tiles.len());
 
-        // fscore
-        // key: position
-        // value: best guess as to total cost from here to finish
-        let mut total_cost_guess = HashMap::new();
-        total_cost_guess.insert(from, (to - from).manhattan() as u32);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let start = Point::from((x, y));
+                let tiles = self.fill(start, connectivity, &mut visited, |a, b| key(a) == key(b));
+                if tiles.is_empty() {
+                    continue;
+                }
+                let id = ComponentId(stats.len());
+                for &point in &tiles {
+                    labels[point] = id;
+                }
+                let touches_border = tiles.iter().any(|point| {
+                    point.x == 0
+                        || point.y == 0
+                        || point.x as usize == self.width - 1
+                        || point.y as usize == self.height - 1
+                });
+                stats.push(ComponentStats {
+                    id,
+                    representative: start,
+                    size: tiles.len(),
+                    bounds: BoundingBox::from_points(tiles.into_iter().map(PointN::from))
+                        .expect("components are never empty"),
+                    touches_border,
+                });
+            }
+        }
+
+        tracing::debug!(components = stats.len());
+        Components { labels, stats }
+    }
+}
+
+/// The cost of moving onto a tile, for weighted searches.
+pub trait StepCost {
+    /// `None` if the tile cannot be moved onto.
+    fn step_cost(&self) -> Option<u32>;
+}
+
+impl StepCost for Traversable {
+    fn step_cost(&self) -> Option<u32> {
+        match self {
+            Traversable::Obstructed => None,
+            Traversable::Free | Traversable::Halt => Some(1),
+        }
+    }
+}
+
+/// A path found by a search of a [`Map`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub directions: Vec<Direction>,
+    /// The sum of the step costs of every tile moved onto.
+    pub cost: u32,
+    /// Where the route ends; useful when searching for any of several targets.
+    pub end: Point,
+}
+
+impl<T: Clone> Map<T> {
+    fn tile_index(&self, point: Point) -> usize {
+        point.x as usize + point.y as usize * self.width
+    }
+
+    /// Find a cheapest route from `from` to whichever of `targets` is cheapest to reach.
+    ///
+    /// `cost` gives the cost of moving onto a tile, or `None` if it can't be. `heuristic` must
+    /// never overestimate the remaining cost from a point to the nearest target; if it is
+    /// always 0, this is Dijkstra's algorithm.
+    #[tracing::instrument(level = "debug", skip(self, cost, heuristic))]
+    pub fn a_star<C, H>(
+        &self,
+        from: Point,
+        targets: &[Point],
+        cost: C,
+        heuristic: H,
+    ) -> Option<Route>
+    where
+        C: Fn(&T, Point) -> Option<u32>,
+        H: Fn(Point) -> u32,
+    {
+        if !self.in_bounds(from) {
+            return None;
+        }
 
-        while let Some(AStarNode { cost, position }) = open_set.pop() {
-            if position == to {
+        // cheapest known cost from the start to each tile, and the step which achieved it
+        let mut best = vec![u32::MAX; self.tiles.len()];
+        let mut came_from: Vec<Option<(Direction, Point)>> = vec![None; self.tiles.len()];
+        let mut open = BinaryHeap::new();
+        best[self.tile_index(from)] = 0;
+        open.push(Reverse((heuristic(from), 0, from)));
+
+        while let Some(Reverse((_, cost_so_far, position))) = open.pop() {
+            // stale entries for tiles since reached more cheaply are skipped rather than removed
+            if cost_so_far > best[self.tile_index(position)] {
+                continue;
+            }
+            tracing::trace!(?position, cost_so_far, open = open.len(), "expanding");
+            if targets.contains(&position) {
+                let mut directions = Vec::new();
                 let mut current = position;
-                let mut path = Vec::new();
-                while let Some((direction, predecessor)) = came_from.remove(&current) {
+                while let Some((direction, predecessor)) = came_from[self.tile_index(current)] {
+                    directions.push(direction);
                     current = predecessor;
-                    path.push(direction);
                 }
-                debug_assert!(path.len() as i32 >= (to - from).manhattan());
-                path.reverse();
-                return Some(path);
+                directions.reverse();
+                tracing::debug!(len = directions.len(), cost = cost_so_far, "found path");
+                return Some(Route {
+                    directions,
+                    cost: cost_so_far,
+                    end: position,
+                });
             }
 
             for direction in Direction::iter() {
@@ -809,73 +2938,670 @@ impl<T: Clone + ContextInto<Traversable>> Map<T> {
                 if !self.in_bounds(neighbor) {
                     continue;
                 }
-                match self[neighbor].clone().ctx_into(context) {
-                    Traversable::Obstructed => {}
-                    Traversable::Free | Traversable::Halt => {
-                        let tentative_cheapest_path_cost = cost + 1;
-                        if tentative_cheapest_path_cost
-                            < cheapest_path_cost
-                                .get(&neighbor)
-                                .cloned()
-                                .unwrap_or(u32::MAX)
-                        {
-                            // this path to the neighbor is better than any previous one
-                            came_from.insert(neighbor, (direction, position));
-                            cheapest_path_cost.insert(neighbor, tentative_cheapest_path_cost);
-                            total_cost_guess.insert(
-                                neighbor,
-                                tentative_cheapest_path_cost + (to - neighbor).manhattan() as u32,
-                            );
-
-                            // this thing with the iterator is not very efficient, but for some weird reason BinaryHeap
-                            // doesn't have a .contains method; see
-                            // https://github.com/rust-lang/rust/issues/66724
-                            if open_set
-                                .iter()
-                                .find(|elem| elem.position == neighbor)
-                                .is_none()
-                            {
-                                open_set.push(AStarNode {
-                                    cost: tentative_cheapest_path_cost,
-                                    position: neighbor,
-                                });
-                            }
-                        }
-                    }
+                let step = match cost(&self[neighbor], neighbor) {
+                    Some(step) => step,
+                    None => continue,
+                };
+                let tentative = cost_so_far.saturating_add(step);
+                let idx = self.tile_index(neighbor);
+                if tentative < best[idx] {
+                    best[idx] = tentative;
+                    came_from[idx] = Some((direction, position));
+                    open.push(Reverse((
+                        tentative.saturating_add(heuristic(neighbor)),
+                        tentative,
+                        neighbor,
+                    )));
                 }
             }
         }
 
+        tracing::debug!("no path");
         None
     }
-}
 
-/// A* State
-// https://doc.rust-lang.org/std/collections/binary_heap/#examples
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
-struct AStarNode {
-    cost: u32,
-    position: Point,
+    /// The cheapest cost of reaching every tile from `from`, or `None` for unreachable tiles.
+    ///
+    /// `cost` is as for [`a_star`](Self::a_star). The start tile's own cost is never counted.
+    #[tracing::instrument(level = "debug", skip(self, cost))]
+    pub fn dijkstra<C>(&self, from: Point, cost: C) -> Map<Option<u32>>
+    where
+        C: Fn(&T, Point) -> Option<u32>,
+    {
+        let mut distances = Map {
+            tiles: vec![None; self.tiles.len()],
+            width: self.width,
+            height: self.height,
+            y_axis: self.y_axis,
+        };
+        if !self.in_bounds(from) {
+            return distances;
+        }
+
+        let mut open = BinaryHeap::new();
+        distances[from] = Some(0);
+        open.push(Reverse((0, from)));
+        while let Some(Reverse((cost_so_far, position))) = open.pop() {
+            if Some(cost_so_far) > distances[position] {
+                continue;
+            }
+            for direction in Direction::iter() {
+                let neighbor = position + direction;
+                if !self.in_bounds(neighbor) {
+                    continue;
+                }
+                if let Some(step) = cost(&self[neighbor], neighbor) {
+                    let tentative = cost_so_far.saturating_add(step);
+                    if distances[neighbor].is_none_or(|known| tentative < known) {
+                        distances[neighbor] = Some(tentative);
+                        open.push(Reverse((tentative, neighbor)));
+                    }
+                }
+            }
+        }
+        distances
+    }
 }
 
-// The priority queue depends on `Ord`.
-// Explicitly implement the trait so the queue becomes a min-heap
-// instead of a max-heap.
-impl Ord for AStarNode {
-    fn cmp(&self, other: &AStarNode) -> std::cmp::Ordering {
-        // Notice that the we flip the ordering on costs.
-        // In case of a tie we compare positions - this step is necessary
-        // to make implementations of `PartialEq` and `Ord` consistent.
-        other
-            .cost
-            .cmp(&self.cost)
-            .then_with(|| self.position.cmp(&other.position))
+impl<T: Clone + StepCost> Map<T> {
+    /// The cheapest step cost of any tile, which scales the Manhattan heuristic so that it
+    /// never overestimates.
+    fn min_step_cost(&self) -> u32 {
+        self.tiles
+            .iter()
+            .filter_map(StepCost::step_cost)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Find a cheapest route between the given points using A*, with tiles' own step costs.
+    pub fn navigate_weighted(&self, from: Point, to: Point) -> Option<Route> {
+        self.navigate_to_any(from, &[to])
+    }
+
+    /// Find a cheapest route from `from` to whichever of `targets` is cheapest to reach.
+    pub fn navigate_to_any(&self, from: Point, targets: &[Point]) -> Option<Route> {
+        let min_step = self.min_step_cost();
+        self.a_star(
+            from,
+            targets,
+            |tile, _| tile.step_cost(),
+            |point| {
+                targets
+                    .iter()
+                    .map(|target| (*target - point).manhattan() as u32 * min_step)
+                    .min()
+                    .unwrap_or(0)
+            },
+        )
+    }
+
+    /// The cheapest cost of reaching every tile from `from` with tiles' own step costs; see
+    /// [`dijkstra`](Self::dijkstra).
+    pub fn distances_from(&self, from: Point) -> Map<Option<u32>> {
+        self.dijkstra(from, |tile, _| tile.step_cost())
     }
 }
 
-// `PartialOrd` needs to be implemented as well.
-impl PartialOrd for AStarNode {
-    fn partial_cmp(&self, other: &AStarNode) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn char_map(rows: &[&str]) -> Map<char> {
+        <Map<char> as TryFrom<&str>>::try_from(rows.join("\n").as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_map_orientations() {
+        let map = char_map(&["ABCD", "EFGH", "IJKL"]);
+        assert_eq!(map.rotate(1), char_map(&["IEA", "JFB", "KGC", "LHD"]));
+        assert_eq!(map.rotate(2), map.flip_horizontal().flip_vertical());
+        assert_eq!(map.rotate(4), map);
+        assert_eq!(map.transpose(), char_map(&["LHD", "KGC", "JFB", "IEA"]));
+        assert_eq!(map.flip_vertical(), char_map(&["IJKL", "EFGH", "ABCD"]));
+
+        let orientations: Vec<_> = map.orientations().map(|o| o.to_map()).collect();
+        assert_eq!(orientations.iter().collect::<HashSet<_>>().len(), 8);
+        for (orientation, oriented) in Orientation::all().zip(&orientations) {
+            assert!(map.oriented(orientation).in_bounds(Point::new(
+                oriented.width() as i32 - 1,
+                oriented.height() as i32 - 1
+            )));
+        }
+
+        assert_eq!(map.edge(Direction::Up), vec!['A', 'B', 'C', 'D']);
+        assert_eq!(map.edge(Direction::Left), vec!['I', 'E', 'A']);
+        assert_eq!(
+            map.oriented(Orientation::new(1, false)).edge(Direction::Up),
+            vec!['I', 'E', 'A']
+        );
+        assert_eq!(map.strip_border(1), char_map(&["FG"]));
+        assert_eq!(map.sub_map(Point::new(2, 1), 2, 2), char_map(&["CD", "GH"]));
+    }
+
+    #[test]
+    fn test_find_pattern() {
+        let map = char_map(&["#..#.", ".##..", "..#..", "#...#"]);
+        let corner = Pattern::<char>::parse("#.\n?#", '?').unwrap();
+        let upright = map.find_pattern(&corner, Some(Orientation::default()));
+        assert_eq!(upright.len(), 1);
+        assert_eq!(upright[0].origin, Point::new(0, 2));
+
+        let all = map.find_pattern(&corner, Orientation::all());
+        assert!(all.len() > upright.len());
+        assert!(all.iter().all(|found| corner.covered(found).count() == 3));
+
+        // a symmetric pattern is only reported once per placement
+        let block = Pattern::<char>::parse("##", '?').unwrap();
+        assert_eq!(map.find_pattern(&block, Orientation::all()).len(), 2);
+
+        let mut marked = map.clone();
+        marked.mark_pattern(&block, &map.find_pattern(&block, Orientation::all()), 'O');
+        assert_eq!(marked, char_map(&["#..#.", ".OO..", "..O..", "#...#"]));
+    }
+
+    #[test]
+    fn test_sparse_map() {
+        let dense = char_map(&["#..", "...", ".##"]);
+        let mut sparse = SparseMap::from_map(&dense, |tile| *tile == '#');
+        assert_eq!(sparse.len(), 3);
+        assert_eq!(sparse.bounds(), Some((Point::new(0, 0), Point::new(2, 2))));
+        assert_eq!(sparse.neighbours(&Point::new(1, 1)).count(), 3);
+
+        sparse.insert(Point::new(-2, 1), 'O');
+        assert_eq!(sparse.to_string(), "..#..\nO....\n...##\n");
+        let (map, origin) = sparse.to_map('.').unwrap();
+        assert_eq!(origin, Point::new(-2, 0));
+        assert_eq!(map[Point::new(0, 1)], 'O');
+        let round_trip: SparseMap<_, _> = SparseMap::from_map(&map, |tile| *tile != '.')
+            .iter()
+            .map(|(point, tile)| (*point + origin, *tile))
+            .collect();
+        assert_eq!(round_trip, sparse);
+    }
+
+    /// Digits are step costs; `#` is a wall.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Cost(Option<u32>);
+
+    impl From<char> for Cost {
+        fn from(c: char) -> Self {
+            Cost(c.to_digit(10))
+        }
+    }
+
+    impl StepCost for Cost {
+        fn step_cost(&self) -> Option<u32> {
+            self.0
+        }
+    }
+
+    impl From<Cost> for Traversable {
+        fn from(cost: Cost) -> Self {
+            match cost.0 {
+                Some(_) => Traversable::Free,
+                None => Traversable::Obstructed,
+            }
+        }
+    }
+
+    fn cost_map(rows: &[&str]) -> Map<Cost> {
+        <Map<Cost> as TryFrom<&str>>::try_from(rows.join("\n").as_str()).unwrap()
+    }
+
+    #[test]
+    fn test_navigate_weighted() {
+        // the straight route along the bottom is short but expensive
+        let map = cost_map(&["1111", "1##1", "1991"]);
+        let (from, to) = (Point::new(0, 0), Point::new(3, 0));
+        let route = map.navigate_weighted(from, to).unwrap();
+        assert_eq!(route.cost, 7);
+        assert_eq!(route.directions.len(), 7);
+        assert_eq!(
+            route
+                .directions
+                .iter()
+                .fold(from, |point, dir| point + *dir),
+            to
+        );
+
+        let nearest = map.navigate_to_any(from, &[Point::new(1, 0), to]).unwrap();
+        assert_eq!((nearest.end, nearest.cost), (to, 7));
+
+        let distances = map.distances_from(from);
+        assert_eq!(distances[to], Some(7));
+        assert_eq!(distances[Point::new(1, 1)], None);
+        assert_eq!(distances[Point::new(3, 2)], Some(5));
+
+        assert_eq!(map.navigate_weighted(from, Point::new(1, 1)), None);
+    }
+
+    #[test]
+    fn test_point_n() {
+        let point: PointN<3> = "<x=-1, y=0, z=2>".parse().unwrap();
+        assert_eq!(point, PointN::new([-1, 0, 2]));
+        assert_eq!(point.to_string().parse(), Ok(point));
+        assert!("1,2".parse::<PointN<3>>().is_err());
+        assert_eq!(
+            "1,2,3,4".parse::<PointN<3>>(),
+            Err("expected 3 coordinates; found 4".to_string())
+        );
+        assert_eq!((point * 2 - point).manhattan(), 3);
+        assert_eq!(point.chebyshev(), 2);
+        assert_eq!(point.neighbours().count(), 26);
+        assert_eq!(point.orthogonal_neighbours().count(), 6);
+
+        let bbox = BoundingBox::from_points(point.neighbours()).unwrap();
+        assert_eq!(bbox.size(), [3, 3, 3]);
+        assert!(bbox.contains(point));
+        assert_eq!(bbox.points().collect::<HashSet<_>>().len(), bbox.volume());
+    }
+
+    #[test]
+    fn test_hex_path() {
+        let path: HexPath = "nwwswee".parse().unwrap();
+        assert_eq!(path.0.len(), 5);
+        assert_eq!(path.follow(HexPoint::default()), HexPoint::default());
+        assert!("esn".parse::<HexPath>().is_err());
+        // non-ASCII input is an error, not a panic
+        assert!("é".parse::<HexPath>().is_err());
+        assert!("né".parse::<HexPath>().is_err());
+        assert!("eé".parse::<HexPath>().is_err());
+    }
+
+    #[test]
+    fn test_hex_rings() {
+        let centre = HexPoint::new(3, -1);
+        for radius in 0..5 {
+            let ring = centre.ring(radius);
+            assert_eq!(ring.len(), (6 * radius).max(1) as usize);
+            assert!(ring.iter().all(|hex| hex.distance(centre) == radius as i32));
+            assert!(ring.windows(2).all(|pair| pair[0].distance(pair[1]) == 1));
+        }
+        let spiral = centre.spiral(3);
+        assert_eq!(spiral.len(), 37);
+        assert_eq!(spiral.iter().collect::<HashSet<_>>().len(), 37);
+    }
+
+    #[test]
+    fn test_hex_rotation() {
+        let hex = HexPoint::new(2, -3);
+        let mut rotated = hex;
+        for _ in 0..6 {
+            assert_eq!(rotated.rotate_right().rotate_left(), rotated);
+            assert_eq!(rotated.distance(HexPoint::default()), 3);
+            rotated = rotated.rotate_right();
+        }
+        assert_eq!(rotated, hex);
+        assert_eq!(
+            HexPoint::from(HexDirection::East).rotate_right(),
+            HexPoint::from(HexDirection::SouthEast)
+        );
+    }
+
+    #[test]
+    fn test_reachable_from_edges() {
+        let map = cost_map(&["1#11", "##11", "1111"]);
+        let mut reached = Vec::new();
+        map.reachable_from(Point::new(3, 2), |_, point| {
+            reached.push(point);
+            false
+        });
+        assert_eq!(reached.len(), 8);
+        assert!(!reached.contains(&Point::new(0, 2)));
+
+        let mut visited = false;
+        map.reachable_from(Point::new(4, 0), |_, _| {
+            visited = true;
+            false
+        });
+        assert!(!visited);
+    }
+
+    #[test]
+    fn test_components() {
+        let map = char_map(&["#..#", ".#..", "..##"]);
+        let orthogonal = map.components(Connectivity::Four, |tile| *tile);
+        let walls = |components: &Components| {
+            components
+                .stats
+                .iter()
+                .filter(|stats| map[stats.representative] == '#')
+                .count()
+        };
+        assert_eq!(walls(&orthogonal), 4);
+        assert_eq!(orthogonal.stats.len(), 6);
+
+        let diagonal = map.components(Connectivity::Eight, |tile| *tile);
+        assert_eq!(walls(&diagonal), 2);
+        let corner = diagonal.at(Point::new(0, 2));
+        assert_eq!(corner.size, 4);
+        assert_eq!(
+            corner.bounds,
+            BoundingBox {
+                min: PointN([0, 0]),
+                max: PointN([3, 2])
+            }
+        );
+        assert!(corner.touches_border);
+        assert_eq!(diagonal.labels[Point::new(1, 1)], corner.id);
+        assert_eq!(diagonal.stats.len(), 3);
+        assert_eq!(diagonal.at(Point::new(0, 0)).size, 7);
+
+        let floor = map.flood_fill(Point::new(0, 0), Connectivity::Four, |_, to| *to == '.');
+        assert_eq!(floor.len(), 3);
+        assert_eq!(floor[0], Point::new(0, 0));
+    }
+
+    #[test]
+    fn test_compass() {
+        assert_eq!(Compass::North.turn(135), Some(Compass::SouthEast));
+        assert_eq!(Compass::North.turn(-450), Some(Compass::West));
+        assert_eq!(Compass::East.turn(30), None);
+        assert_eq!(Compass::iter().filter(|c| c.is_diagonal()).count(), 4);
+        assert_eq!("nw".parse::<Compass>(), Ok(Compass::NorthWest));
+        assert!("NS".parse::<Compass>().is_err());
+        assert_eq!(Direction::Up.turn(270), Some(Direction::Left));
+        assert_eq!(Direction::Up.turn(45), None);
+        assert_eq!(
+            Direction::try_from(Compass::SouthWest),
+            Err(Compass::SouthWest)
+        );
+        assert_eq!(
+            Compass::from_letter('W').map(Point::from),
+            Some(Point::new(-1, 0))
+        );
+        assert_eq!(Turn::from_letter('L').map(|t| t.degrees(90)), Some(-90));
+
+        let point = Point::new(10, 4);
+        assert_eq!(point.rotate(90), Some(Point::new(4, -10)));
+        assert_eq!(point.rotate(-90), Some(Point::new(-4, 10)));
+        assert_eq!(point.rotate(360), Some(point));
+        assert_eq!(point.rotate(45), None);
+        assert_eq!(
+            point.rotate_about(Point::new(10, 0), 180),
+            Some(Point::new(10, -4))
+        );
+    }
+
+    #[test]
+    fn test_map_views() {
+        let map = char_map(&["abc", "def"]);
+        assert_eq!(map.view(Edges::TORUS)[Point::new(-1, 2)], 'f');
+        assert_eq!(map.view(Edges::WRAP_X).get(Point::new(4, 2)), None);
+        assert_eq!(map.view(Edges::WRAP_Y)[Point::new(1, -1)], 'b');
+        assert_eq!(map.view(Edges::CLAMP)[Point::new(-5, 9)], 'a');
+
+        let ray: String = map
+            .view(Edges::WRAP_X)
+            .ray(Point::new(0, 1), Point::new(2, -1))
+            .map(|(_, tile)| *tile)
+            .collect();
+        assert_eq!(ray, "f");
+        let ray: Vec<_> = map
+            .view(Edges::TORUS)
+            .ray(Point::new(0, 0), Point::new(1, 1))
+            .take(3)
+            .map(|(point, _)| point)
+            .collect();
+        assert_eq!(
+            ray,
+            vec![Point::new(1, 1), Point::new(2, 0), Point::new(0, 1)]
+        );
+        assert_eq!(
+            map.view(Edges::BOUNDED)
+                .ray(Point::new(0, 0), Point::new(1, 0))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_visibility() {
+        let map = char_map(&["#..#", "...#", "#.L#"]);
+        let seat = Point::new(2, 0);
+        let is_opaque = |tile: &char| *tile != '.';
+        assert_eq!(
+            map.first_visible(seat, Compass::NorthEast, is_opaque),
+            Some(Point::new(3, 1))
+        );
+        assert_eq!(map.first_visible(seat, Compass::North, is_opaque), None);
+        assert_eq!(
+            map.first_visible(seat, Compass::NorthWest, is_opaque),
+            Some(Point::new(0, 2))
+        );
+
+        let graph = map.visibility_graph(|tile| *tile == 'L', is_opaque);
+        assert_eq!(graph.visible(seat).len(), 4);
+        assert!(graph.visible(Point::new(0, 0)).is_empty());
+        let adjacent = map.visibility_graph(|tile| *tile == 'L', |_| true);
+        assert_eq!(adjacent.visible(seat).len(), 5);
+    }
+
+    #[test]
+    fn test_intersect() {
+        let line = |x0, y0, x1, y1| Line::new(Point::new(x0, y0), Point::new(x1, y1));
+        assert_eq!(
+            intersect(line(0, 0, 4, 0), line(2, -3, 2, 3)),
+            Some(Intersection::Point(Point::new(2, 0)))
+        );
+        assert_eq!(
+            intersect(line(0, 0, 1, 1), line(0, 1, 1, 0)),
+            Some(Intersection::Fraction {
+                x: 1,
+                y: 1,
+                denominator: 2
+            })
+        );
+        assert_eq!(intersect(line(0, 0, 4, 0), line(5, -3, 5, 3)), None);
+        assert_eq!(intersect(line(0, 0, 4, 0), line(0, 1, 4, 1)), None);
+        assert_eq!(
+            intersect(line(0, 0, 4, 0), line(6, 0, 2, 0)),
+            Some(Intersection::Overlap(line(2, 0, 4, 0)))
+        );
+        assert_eq!(
+            intersect(line(0, 0, 2, 2), line(2, 2, 3, 3)),
+            Some(Intersection::Point(Point::new(2, 2)))
+        );
+        assert_eq!(
+            intersect(line(3, 3, 3, 3), line(0, 0, 6, 6)),
+            Some(Intersection::Point(Point::new(3, 3)))
+        );
+        assert_eq!(
+            intersect(line(0, 0, 6, 3), line(8, 4, -2, -1))
+                .unwrap()
+                .lattice_points(),
+            vec![
+                Point::new(0, 0),
+                Point::new(2, 1),
+                Point::new(4, 2),
+                Point::new(6, 3)
+            ]
+        );
+
+        // far enough apart that f32 couldn't tell neighbouring points apart
+        let big = 1 << 30;
+        assert_eq!(
+            intersect(line(-big, 1, big, 1), line(big - 1, -big, big - 1, big)),
+            Some(Intersection::Point(Point::new(big - 1, 1)))
+        );
+    }
+
+    #[test]
+    fn test_sweep_intersections() {
+        let mut rng = crate::generate::Rng::new(1);
+        let coord = |rng: &mut crate::generate::Rng| rng.range(0..=40) as i32 - 20;
+        let wire = |rng: &mut crate::generate::Rng, diagonals: bool| {
+            (0..60)
+                .map(|_| {
+                    let from = Point::new(coord(rng), coord(rng));
+                    let to = match rng.range(0..=2) {
+                        0 => Point::new(coord(rng), from.y),
+                        1 => Point::new(from.x, coord(rng)),
+                        _ if diagonals => Point::new(coord(rng), coord(rng)),
+                        _ => from,
+                    };
+                    Line::new(from, to)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for &diagonals in &[false, true] {
+            let (a, b) = (wire(&mut rng, diagonals), wire(&mut rng, diagonals));
+            let mut naive = intersections_naive(&a, &b);
+            let mut swept = intersections(&a, &b);
+            assert!(!naive.is_empty());
+            naive.sort_unstable();
+            swept.sort_unstable();
+            assert_eq!(swept, naive);
+        }
+    }
+
+    #[test]
+    fn test_map_text_round_trip() {
+        let map = char_map(&["#..", ".#.", "..L"]);
+        let text = map.to_text();
+        assert!(text.starts_with("map 3x3 y-up\n#..\n"));
+        let parsed = Map::<char>::from_text(&text).unwrap();
+        assert_eq!(parsed, map);
+        assert_eq!(parsed.to_text(), text);
+
+        let empty: Map<char> = Map::from(&[] as &[Vec<char>]);
+        assert_eq!(Map::<char>::from_text(&empty.to_text()).unwrap(), empty);
+        let narrow = Map::<char>::from_text("map 0x2 y-up\n\n\n").unwrap();
+        assert_eq!((narrow.width(), narrow.height()), (0, 2));
+        assert_eq!(Map::<char>::from_text(&narrow.to_text()).unwrap(), narrow);
+
+        assert_eq!(
+            Map::<char>::from_text("map 3x2 y-up\n#..\n"),
+            Err(MapConversionErr::WrongSize {
+                expected: (3, 2),
+                found: (3, 1)
+            })
+        );
+        assert!(matches!(
+            Map::<char>::from_text("#..\n"),
+            Err(MapConversionErr::BadHeader(_))
+        ));
+    }
+
+    #[cfg(feature = "geometry-serde")]
+    #[test]
+    fn test_serde() {
+        let map = char_map(&["#..", ".#."]);
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<Map<char>>(&json).unwrap(), map);
+        assert!(serde_json::from_str::<Map<char>>(r#"{"tiles":[],"width":1,"height":1}"#).is_err());
+
+        let point = PointN([1, -2, 3]);
+        assert_eq!(serde_json::to_string(&point).unwrap(), "[1,-2,3]");
+        assert_eq!(
+            serde_json::from_str::<PointN<3>>("[1,-2,3]").unwrap(),
+            point
+        );
+        assert!(serde_json::from_str::<PointN<3>>("[1,-2]").is_err());
+
+        let line = Line::new(Point::new(0, 1), Point::new(2, 3));
+        let json = serde_json::to_string(&line).unwrap();
+        assert_eq!(serde_json::from_str::<Line>(&json).unwrap(), line);
+        let compass: Compass =
+            serde_json::from_str(&serde_json::to_string(&Compass::SouthWest).unwrap()).unwrap();
+        assert_eq!(compass, Compass::SouthWest);
+    }
+
+    #[test]
+    fn test_y_down() {
+        let up = char_map(&["ABCD", "EFGH", "IJKL"]);
+        let down = up.clone().with_y_axis(YAxis::Down);
+        assert_eq!(down[Point::new(0, 0)], 'A');
+        assert_eq!(down[Point::new(1, 2)], 'J');
+        assert_eq!(down.to_string(), up.to_string());
+        assert_ne!(down, up);
+
+        let text = down.to_text();
+        assert!(text.starts_with("map 4x3 y-down\n"));
+        assert_eq!(Map::<char>::from_text(&text).unwrap(), down);
+
+        let point = Point::new(1, 2);
+        assert_eq!(down[point], up[down.convert_point(point, YAxis::Up)]);
+        assert_eq!(down[down.step_on_page(point, Direction::Up)], 'F');
+        assert_eq!(up[up.step_on_page(Point::new(1, 0), Direction::Up)], 'F');
+        let centre = Point::new(1, 1);
+        assert_eq!(
+            centre + Direction::Up,
+            down.step_on_page(centre, Direction::Down)
+        );
+        assert_eq!(down[centre + Direction::Up], 'J');
+
+        assert_eq!(down.rotate(1).to_string(), up.rotate(1).to_string());
+        assert_eq!(down.rotate(1)[Point::new(0, 0)], 'I');
+        // directions follow the axes: the up edge is where up steps lead off the map
+        for (side, start) in [
+            (Direction::Up, Point::new(0, 2)),
+            (Direction::Down, Point::new(0, 0)),
+            (Direction::Left, Point::new(0, 0)),
+            (Direction::Right, Point::new(3, 0)),
+        ]
+        .iter()
+        {
+            assert!(!down.in_bounds(*start + *side));
+            assert_eq!(down.edge(*side)[0], down[*start]);
+        }
+        assert_eq!(down.edge(Direction::Up), vec!['I', 'J', 'K', 'L']);
+        assert_eq!(down.edge(Direction::Left), vec!['A', 'E', 'I']);
+        assert_eq!(down.edge_on_page(Direction::Up), up.edge(Direction::Up));
+        assert_eq!(down.edge_on_page(Direction::Left), up.edge(Direction::Left));
+        assert_eq!(
+            down.oriented(Orientation::new(0, true))[Point::new(0, 0)],
+            'D'
+        );
+        let sub = down.sub_map(Point::new(1, 0), 2, 2);
+        assert_eq!(sub.to_string(), "BC\nFG\n");
+        assert_eq!(sub.y_axis(), YAxis::Down);
+
+        let pattern = Pattern::<char>::parse("F\nJ", ' ').unwrap();
+        let mut marked = down.clone();
+        let found = marked.find_pattern(&pattern, Orientation::all());
+        assert_eq!(
+            found,
+            vec![PatternMatch {
+                origin: Point::new(1, 1),
+                orientation: Orientation::default(),
+                y_axis: YAxis::Down,
+            }]
+        );
+        assert_eq!(
+            up.find_pattern(&pattern, Orientation::all())[0].origin,
+            Point::new(1, 0)
+        );
+        let covered = pattern.covered_cells(&found);
+        assert_eq!(
+            covered
+                .iter()
+                .map(|&point| down[point])
+                .collect::<HashSet<_>>(),
+            ['F', 'J'].iter().copied().collect()
+        );
+        marked.mark_pattern(&pattern, &found, '*');
+        assert_eq!(marked.to_string(), "ABCD\nE*GH\nI*KL\n");
+        let mut remarked = up.clone();
+        remarked.mark_pattern(&pattern, &found, '*');
+        assert_eq!(remarked.to_string(), marked.to_string());
+
+        // tiles are visited in the order of their points
+        let mut visited = Vec::new();
+        down.for_each_point(|&tile, _| visited.push(tile));
+        assert_eq!(down.iter().copied().collect::<Vec<_>>(), visited);
+        assert_eq!(visited[..4], ['A', 'B', 'C', 'D']);
+        let mut visited = Vec::new();
+        down.for_each(|&tile| visited.push(tile));
+        assert_eq!(visited[..4], ['A', 'B', 'C', 'D']);
+        let mut numbered = down.clone();
+        let mut next = b'a';
+        numbered.for_each_mut(|tile| {
+            *tile = next as char;
+            next += 1;
+        });
+        assert_eq!(numbered[Point::new(0, 0)], 'a');
     }
 }
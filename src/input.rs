@@ -3,21 +3,55 @@ use std::io::prelude::*;
 use std::io::BufReader;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Nanoseconds spent inside the iterators produced by this module.
+static PARSE_NANOS: AtomicU64 = AtomicU64::new(0);
+
+/// Total time spent reading and parsing input through this module since the last reset.
+///
+/// The parsers are lazy, so this counts only the time spent inside their iterators,
+/// not whatever the caller does between items.
+pub fn parse_time() -> Duration {
+    Duration::from_nanos(PARSE_NANOS.load(Ordering::Relaxed))
+}
+
+/// Reset the counter behind [`parse_time`].
+pub fn reset_parse_time() {
+    PARSE_NANOS.store(0, Ordering::Relaxed);
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let out = f();
+    PARSE_NANOS.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    out
+}
 
 pub fn parse<T>(path: &Path) -> std::io::Result<impl Iterator<Item = T>>
 where
     T: FromStr,
 {
-    let file = File::open(path)?;
+    tracing::debug!(path = %path.display(), "parsing lines");
+    let file = timed(|| File::open(path))?;
     let mut reader = BufReader::new(file);
     let mut buf = String::new();
+    let mut items = 0_usize;
     Ok(std::iter::from_fn(move || {
-        buf.clear();
-        reader
-            .read_line(&mut buf)
-            .map_err(|_| ())
-            .and_then(|_| T::from_str(buf.trim()).map_err(|_| ()))
-            .ok()
+        let item = timed(|| {
+            buf.clear();
+            reader
+                .read_line(&mut buf)
+                .map_err(|_| ())
+                .and_then(|_| T::from_str(buf.trim()).map_err(|_| ()))
+                .ok()
+        });
+        match item {
+            Some(_) => items += 1,
+            None => tracing::debug!(items, "finished parsing lines"),
+        }
+        item
     })
     .fuse())
 }
@@ -61,9 +95,11 @@ pub fn parse_newline_sep<T>(path: &Path) -> std::io::Result<impl Iterator<Item =
 where
     T: FromStr,
 {
-    let file = File::open(path)?;
+    tracing::debug!(path = %path.display(), "parsing blank-line-separated clusters");
+    let file = timed(|| File::open(path))?;
     let mut reader = BufReader::new(file);
     let mut buf = String::new();
+    let mut items = 0_usize;
 
     fn is_new_field(buf: &str) -> bool {
         let patterns = ["\n\n", "\n\r\n"];
@@ -77,17 +113,24 @@ where
     }
 
     Ok(std::iter::from_fn(move || {
-        buf.clear();
-        while buf.is_empty() || !is_new_field(&buf) {
-            if reader.read_line(&mut buf).ok()? == 0 {
-                break;
+        let item = timed(|| {
+            buf.clear();
+            while buf.is_empty() || !is_new_field(&buf) {
+                if reader.read_line(&mut buf).ok()? == 0 {
+                    break;
+                }
             }
+            if buf.is_empty() {
+                None
+            } else {
+                T::from_str(&buf).ok()
+            }
+        });
+        match item {
+            Some(_) => items += 1,
+            None => tracing::debug!(items, "finished parsing clusters"),
         }
-        if buf.is_empty() {
-            None
-        } else {
-            T::from_str(&buf).ok()
-        }
+        item
     })
     .fuse())
 }
@@ -1,7 +1,17 @@
+pub mod automaton;
+pub mod batch;
 pub mod config;
 pub mod day;
+pub mod generate;
 pub mod geometry;
 pub mod input;
+pub mod math;
+pub mod render;
+pub mod runner;
+pub mod search;
+pub mod sums;
+pub mod watch;
 pub mod website;
+pub mod workspace;
 
 pub use input::{parse, parse_newline_sep, CommaSep};
@@ -1,4 +1,6 @@
 use aoc2020::config::Config;
+use aoc2020::runner::Format;
+use aoc2020::workspace::Invocation;
 use chrono::{Datelike, Utc};
 use color_eyre::eyre::{bail, Result};
 use path_absolutize::Absolutize;
@@ -36,6 +38,66 @@ enum Subcommand {
         #[structopt(flatten)]
         day: Day,
     },
+    /// Run many puzzles in parallel
+    Run {
+        /// day to run; may be repeated (default: every initialized day)
+        #[structopt(short, long = "day")]
+        days: Vec<u8>,
+
+        /// number of parts to run at once (default: available parallelism)
+        #[structopt(short, long)]
+        jobs: Option<usize>,
+
+        /// skip part 1
+        #[structopt(long = "no-part1")]
+        no_part1: bool,
+
+        /// run part 2
+        #[structopt(long)]
+        part2: bool,
+
+        /// build in release mode
+        #[structopt(long)]
+        release: bool,
+
+        /// output format: human, json or tsv
+        #[structopt(long, default_value = "human")]
+        format: Format,
+    },
+    /// Rebuild and rerun a puzzle whenever its sources or input change
+    Watch {
+        #[structopt(flatten)]
+        day: Day,
+
+        /// input file
+        #[structopt(short, long, parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// skip part 1
+        #[structopt(long = "no-part1")]
+        no_part1: bool,
+
+        /// run part 2
+        #[structopt(long)]
+        part2: bool,
+
+        /// build in release mode
+        #[structopt(long)]
+        release: bool,
+    },
+    /// Generate a random input for a puzzle
+    Generate {
+        #[structopt(flatten)]
+        day: Day,
+
+        /// the same seed always generates the same input
+        #[structopt(short, long)]
+        seed: u64,
+
+        /// how many times larger than a typical input to make it, where the puzzle allows
+        #[structopt(long, default_value = "1")]
+        scale: usize,
+    },
 }
 
 impl Subcommand {
@@ -51,6 +113,52 @@ impl Subcommand {
                 aoc2020::day::initialize(&config, day.into())?;
                 Ok(())
             }
+            Self::Run {
+                days,
+                jobs,
+                no_part1,
+                part2,
+                release,
+                format,
+            } => {
+                let options = aoc2020::batch::Options {
+                    days,
+                    part1: !no_part1,
+                    part2,
+                    jobs,
+                    release,
+                    format,
+                };
+                let failures = aoc2020::batch::run(&options)?;
+                if failures > 0 {
+                    bail!("{} part(s) failed", failures);
+                }
+                Ok(())
+            }
+            Self::Watch {
+                day,
+                input,
+                no_part1,
+                part2,
+                release,
+            } => {
+                let config = Config::load()?;
+                let options = aoc2020::watch::Options {
+                    day: day.into(),
+                    invocation: Invocation {
+                        input,
+                        part1: !no_part1,
+                        part2,
+                    },
+                    release,
+                };
+                aoc2020::watch::watch(&config, &options)?;
+                Ok(())
+            }
+            Self::Generate { day, seed, scale } => {
+                print!("{}", aoc2020::generate::generate(day.into(), seed, scale)?);
+                Ok(())
+            }
         }
     }
 }
@@ -0,0 +1,392 @@
+//! Number theory: gcds, modular arithmetic, the Chinese remainder theorem and discrete
+//! logarithms.
+//!
+//! Modular products are computed without overflow: `u64` operands are widened to `u128`, and
+//! `u128` operands are multiplied by doubling.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use thiserror::Error;
+
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// `None` if the result overflows.
+pub fn checked_lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The extended Euclidean algorithm: `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`.
+///
+/// `g` is never negative.
+pub fn egcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` for which `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inv(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let (g, x, _) = egcd(a as i128, m as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m as i128) as u64)
+}
+
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// As [`mul_mod`], for operands too large to widen.
+pub fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+    let (mut a, mut b) = (a % m, b % m);
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let add_mod = |x: u128, y: u128| {
+        // x + y may overflow, but x + y - m never does when it matters
+        if x >= m - y {
+            x - (m - y)
+        } else {
+            x + y
+        }
+    };
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a);
+        }
+        a = add_mod(a, a);
+        b >>= 1;
+    }
+    product
+}
+
+/// `base` to the power `exp`, modulo `m`, by repeated squaring.
+pub fn pow_mod(base: u64, exp: u64, m: u64) -> u64 {
+    pow_mod_u128(base as u128, exp as u128, m as u128) as u64
+}
+
+/// As [`pow_mod`], for operands too large to widen.
+pub fn pow_mod_u128(base: u128, mut exp: u128, m: u128) -> u128 {
+    if m == 1 {
+        return 0;
+    }
+    let mut base = base % m;
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u128(result, base, m);
+        }
+        base = mul_mod_u128(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("moduli must be positive")]
+    ZeroModulus,
+    #[error("x ≡ {} (mod {}) contradicts x ≡ {} (mod {})", .0.residue, .0.modulus, .1.residue, .1.modulus)]
+    Inconsistent(Congruence, Congruence),
+    #[error("the combined modulus overflows u128")]
+    Overflow,
+}
+
+/// Every `x` such that `x ≡ residue (mod modulus)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Congruence {
+    /// Always less than the modulus.
+    pub residue: u128,
+    pub modulus: u128,
+}
+
+impl Congruence {
+    /// `residue` may be negative or larger than the modulus.
+    pub fn new(residue: i128, modulus: u64) -> Result<Congruence, Error> {
+        if modulus == 0 {
+            return Err(Error::ZeroModulus);
+        }
+        Ok(Congruence {
+            residue: residue.rem_euclid(modulus as i128) as u128,
+            modulus: modulus as u128,
+        })
+    }
+
+    /// The `x` satisfying both this congruence and `other`; the moduli need not be coprime.
+    ///
+    /// `other`'s modulus must fit in a `u64`, as must every modulus from [`Congruence::new`].
+    pub fn combine(self, other: Congruence) -> Result<Congruence, Error> {
+        let (a1, m1) = (self.residue, self.modulus);
+        let (a2, m2) = (other.residue, other.modulus);
+        let g = gcd(m1, m2);
+        let difference = (a2 + m2 - a1 % m2) % m2;
+        if difference % g != 0 {
+            return Err(Error::Inconsistent(self, other));
+        }
+        let modulus = checked_lcm(m1, m2).ok_or(Error::Overflow)?;
+
+        // a1 + m1 * k ≡ a2 (mod m2), so (m1 / g) * k ≡ (a2 - a1) / g (mod m2 / g)
+        let reduced = u64::try_from(m2 / g).map_err(|_| Error::Overflow)?;
+        let inverse = mod_inv(((m1 / g) % reduced as u128) as u64, reduced)
+            .expect("m1 / g and m2 / g are coprime");
+        let k = mul_mod(
+            ((difference / g) % reduced as u128) as u64,
+            inverse,
+            reduced,
+        );
+        // m1 * k < m1 * (m2 / g) == modulus, so this can't overflow, but a1 + step might
+        let step = m1 * k as u128;
+        let residue = if a1 >= modulus - step {
+            a1 - (modulus - step)
+        } else {
+            a1 + step
+        };
+        Ok(Congruence { residue, modulus })
+    }
+}
+
+/// The Chinese remainder theorem: the single congruence satisfied by exactly those `x`
+/// satisfying every `x ≡ residue (mod modulus)` given.
+///
+/// Moduli need not be pairwise coprime, but if they aren't the congruences may contradict each
+/// other. With no congruences at all, every integer is a solution.
+pub fn crt<I>(congruences: I) -> Result<Congruence, Error>
+where
+    I: IntoIterator<Item = (i128, u64)>,
+{
+    congruences.into_iter().try_fold(
+        Congruence {
+            residue: 0,
+            modulus: 1,
+        },
+        |solution, (residue, modulus)| solution.combine(Congruence::new(residue, modulus)?),
+    )
+}
+
+/// The prime factorization of `n`, as ascending `(prime, exponent)` pairs, by trial division.
+///
+/// Takes up to `sqrt(n)` divisions, so it's only quick for `n` with small factors or of modest
+/// size.
+pub fn prime_factors(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut divisor = 2;
+    while divisor <= n / divisor {
+        let mut exponent = 0;
+        while n.is_multiple_of(divisor) {
+            n /= divisor;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((divisor, exponent));
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// The least positive `k` for which `base^k ≡ 1 (mod m)`, given any multiple `group_order` of
+/// it, such as the size of the multiplicative group mod `m` (`m - 1` for prime `m`).
+///
+/// `None` if `base^group_order` isn't 1.
+pub fn multiplicative_order(base: u64, m: u64, group_order: u64) -> Option<u64> {
+    if group_order == 0 || pow_mod(base, group_order, m) != 1 % m {
+        return None;
+    }
+    let mut order = group_order;
+    for (prime, exponent) in prime_factors(group_order) {
+        for _ in 0..exponent {
+            if pow_mod(base, order / prime, m) != 1 % m {
+                break;
+            }
+            order /= prime;
+        }
+    }
+    Some(order)
+}
+
+/// The least `x < bound` for which `base^x ≡ target (mod m)`, by baby-step giant-step.
+///
+/// Takes time and space proportional to `sqrt(bound)`. `base` must be coprime to `m`.
+fn bsgs(base: u64, target: u64, m: u64, bound: u64) -> Option<u64> {
+    let target = target % m;
+    if target == 1 % m {
+        return Some(0);
+    }
+    let steps = (bound as f64).sqrt().ceil() as u64 + 1;
+
+    // keep the first, and so least, exponent reaching each value
+    let mut baby_steps = HashMap::new();
+    let mut power = 1 % m;
+    for j in 0..steps {
+        baby_steps.entry(power).or_insert(j);
+        power = mul_mod(power, base, m);
+    }
+
+    // power is now base^steps; look for target * base^-(steps * i) among the baby steps
+    let giant_step = mod_inv(power, m)?;
+    let mut value = target;
+    for i in 0..steps {
+        if let Some(&j) = baby_steps.get(&value) {
+            let x = i * steps + j;
+            return if x < bound { Some(x) } else { None };
+        }
+        value = mul_mod(value, giant_step, m);
+    }
+    None
+}
+
+/// The discrete logarithm: the least `x` for which `base^x ≡ target (mod m)`, if there is one.
+///
+/// Uses baby-step giant-step, which takes time and space proportional to `sqrt(m)`. `base` must
+/// be coprime to `m`; see [`pohlig_hellman`] when the group's order has only small factors.
+pub fn discrete_log(base: u64, target: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    bsgs(base, target, m, m)
+}
+
+/// As [`discrete_log`], given any multiple `group_order` of the order of `base`, such as `m - 1`
+/// for prime `m`.
+///
+/// Solves for `x` modulo each prime power dividing the order of `base` and combines the results
+/// with the [`crt`], so this is fast whenever those prime powers are small, however large `m`.
+pub fn pohlig_hellman(base: u64, target: u64, m: u64, group_order: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    let order = multiplicative_order(base, m, group_order)?;
+    let congruences = prime_factors(order)
+        .into_iter()
+        .map(|(prime, exponent)| {
+            // project into the subgroup of order prime^exponent
+            let prime_power = prime.pow(exponent);
+            let cofactor = order / prime_power;
+            let x = bsgs(
+                pow_mod(base, cofactor, m),
+                pow_mod(target, cofactor, m),
+                m,
+                prime_power,
+            )?;
+            Some((x as i128, prime_power))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let x = crt(congruences).ok()?.residue as u64;
+
+    // every projection may have a solution without target being a power of base at all
+    if pow_mod(base, x, m) == target % m {
+        Some(x)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverses() {
+        let (g, x, y) = egcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(egcd(-4, 6).0, 2);
+
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(6, 9), None);
+        assert_eq!(mod_inv(1, 1), Some(0));
+
+        assert_eq!(pow_mod(4, 13, 497), 445);
+        assert_eq!(pow_mod(7, 0, 1), 0);
+        let big = u128::MAX - 158; // prime
+        assert_eq!(mul_mod_u128(big - 1, big - 1, big), 1);
+        // Fermat's little theorem
+        assert_eq!(pow_mod_u128(3, big - 1, big), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        let solution = crt(vec![(2, 3), (3, 5), (2, 7)]).unwrap();
+        assert_eq!((solution.residue, solution.modulus), (23, 105));
+
+        // not coprime, but consistent
+        let solution = crt(vec![(2, 4), (4, 6)]).unwrap();
+        assert_eq!((solution.residue, solution.modulus), (10, 12));
+        assert!(matches!(
+            crt(vec![(1, 4), (2, 6)]),
+            Err(Error::Inconsistent(_, _))
+        ));
+        assert_eq!(crt(vec![(1, 0)]), Err(Error::ZeroModulus));
+
+        let solution = crt(vec![(-1, 1_000_000_007), (0, 998_244_353)]).unwrap();
+        assert_eq!(solution.modulus, 1_000_000_007 * 998_244_353);
+        assert_eq!(solution.residue % 998_244_353, 0);
+        assert_eq!((solution.residue + 1) % 1_000_000_007, 0);
+
+        let primes = [
+            18446744073709551557_u64,
+            18446744073709551533,
+            18446744073709551521,
+        ];
+        assert_eq!(crt(primes.iter().map(|&p| (0, p))), Err(Error::Overflow));
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(
+            prime_factors(20201226),
+            vec![(2, 1), (3, 1), (29, 1), (116099, 1)]
+        );
+        assert_eq!(prime_factors(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(multiplicative_order(2, 7, 6), Some(3));
+        assert_eq!(multiplicative_order(3, 7, 6), Some(6));
+
+        assert_eq!(discrete_log(3, 13, 17), Some(4));
+        assert_eq!(discrete_log(2, 1, 7), Some(0));
+        // powers of 2 mod 7 are 1, 2 and 4
+        assert_eq!(discrete_log(2, 3, 7), None);
+        assert_eq!(pohlig_hellman(2, 3, 7, 6), None);
+
+        let p = 1_000_000_007;
+        for &x in &[0, 1, 12345, 500_000_002, 1_000_000_005] {
+            let target = pow_mod(5, x, p);
+            assert_eq!(discrete_log(5, target, p), Some(x));
+            assert_eq!(pohlig_hellman(5, target, p, p - 1), Some(x));
+        }
+        // 2 has order (p - 1) / 2 mod p, so exponents wrap early
+        let x = 700_000_000;
+        assert_eq!(
+            pohlig_hellman(2, pow_mod(2, x, p), p, p - 1),
+            Some(x - (p - 1) / 2)
+        );
+    }
+}
@@ -0,0 +1,466 @@
+use crate::geometry::{GridPoint, Map, Point, SparseMap, YAxis};
+use lazy_static::lazy_static;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// A colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+/// A rectangle of pixels, one per grid tile, positioned in the coordinate space of the grid it
+/// was drawn from.
+///
+/// Like [`Map`], its origin is in the lower left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    origin: Point,
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Frame {
+    /// A blank frame whose lower left pixel is at `origin`.
+    pub fn new(origin: Point, width: usize, height: usize, background: Rgb) -> Frame {
+        Frame {
+            origin,
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Draw each tile of a map with the colour `colour` assigns it.
+    pub fn from_map<T, F>(map: &Map<T>, colour: F) -> Frame
+    where
+        T: Clone,
+        F: Fn(&T) -> Rgb,
+    {
+        let mut frame = Frame::new(Point::default(), map.width(), map.height(), Rgb::BLACK);
+        map.for_each_point(|tile, point| {
+            frame.set(map.convert_point(point, YAxis::Up), colour(tile))
+        });
+        frame
+    }
+
+    /// Draw the occupied region of a sparse map, with vacant tiles in `background`.
+    pub fn from_sparse<P, T, F>(map: &SparseMap<P, T>, colour: F, background: Rgb) -> Frame
+    where
+        P: GridPoint + Into<Point>,
+        F: Fn(&T) -> Rgb,
+    {
+        Frame::from_points(
+            map.iter()
+                .map(|(point, tile)| ((*point).into(), colour(tile))),
+            background,
+        )
+    }
+
+    /// Draw the smallest frame containing every point, with undrawn pixels in `background`.
+    pub fn from_points<I>(points: I, background: Rgb) -> Frame
+    where
+        I: IntoIterator<Item = (Point, Rgb)>,
+    {
+        let points: Vec<_> = points.into_iter().collect();
+        let mut frame = match points.first() {
+            None => Frame::new(Point::default(), 0, 0, background),
+            Some((first, _)) => {
+                let (min, max) = points.iter().fold((*first, *first), |(min, max), (p, _)| {
+                    (min.min_coords(p), max.max_coords(p))
+                });
+                let size = max - min + Point::new(1, 1);
+                Frame::new(min, size.x as usize, size.y as usize, background)
+            }
+        };
+        for (point, colour) in points {
+            frame.set(point, colour);
+        }
+        frame
+    }
+
+    fn index(&self, point: Point) -> Option<usize> {
+        let offset = point - self.origin;
+        if offset.x < 0
+            || offset.y < 0
+            || offset.x as usize >= self.width
+            || offset.y as usize >= self.height
+        {
+            return None;
+        }
+        Some(offset.x as usize + offset.y as usize * self.width)
+    }
+
+    /// The colour of the pixel at a grid position, if it is within the frame.
+    pub fn get(&self, point: Point) -> Option<Rgb> {
+        self.index(point).map(|idx| self.pixels[idx])
+    }
+
+    /// Panics if the point is not within the frame.
+    pub fn set(&mut self, point: Point, colour: Rgb) {
+        let idx = self.index(point).expect("point must be within the frame");
+        self.pixels[idx] = colour;
+    }
+
+    /// This frame with each pixel drawn as a `factor` by `factor` square.
+    ///
+    /// Grid positions scale too, so that scaled frames stay aligned with each other.
+    pub fn scaled(&self, factor: usize) -> Frame {
+        let mut scaled = Frame::new(
+            self.origin * factor as i32,
+            self.width * factor,
+            self.height * factor,
+            Rgb::BLACK,
+        );
+        for (idx, pixel) in scaled.pixels.iter_mut().enumerate() {
+            let (x, y) = (idx % scaled.width, idx / scaled.width);
+            *pixel = self.pixels[x / factor + (y / factor) * self.width];
+        }
+        scaled
+    }
+
+    /// This frame's pixels in the order image formats expect: top row first.
+    fn rows(&self) -> impl Iterator<Item = &[Rgb]> {
+        self.pixels.chunks(self.width.max(1)).rev()
+    }
+
+    fn rgb_bytes(&self) -> Vec<u8> {
+        self.rows()
+            .flatten()
+            .flat_map(|Rgb(r, g, b)| vec![*r, *g, *b])
+            .collect()
+    }
+
+    /// Write a binary PPM (P6) image.
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.rgb_bytes())?;
+        Ok(())
+    }
+
+    /// Write a PNG image.
+    pub fn write_png<W: Write>(&self, writer: W) -> Result<(), Error> {
+        if self.pixels.is_empty() {
+            return Err(Error::Empty);
+        }
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.rgb_bytes())?;
+        Ok(())
+    }
+
+    /// Write an image in the format given by the path's extension: `png` or `ppm`.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        match extension.to_ascii_lowercase().as_str() {
+            "png" => self.write_png(BufWriter::new(File::create(path)?)),
+            "ppm" => self.write_ppm(BufWriter::new(File::create(path)?)),
+            _ => Err(Error::UnknownFormat(path.to_owned())),
+        }
+    }
+}
+
+/// A sequence of frames, such as the steps of a simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Animation {
+    pub frames: Vec<Frame>,
+    /// Hundredths of a second to show each frame for.
+    pub delay: u16,
+    /// The colour of any part of the canvas which a frame doesn't cover.
+    pub background: Rgb,
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Animation {
+            frames: Vec::new(),
+            delay: 10,
+            background: Rgb::BLACK,
+        }
+    }
+}
+
+impl Animation {
+    pub fn new() -> Animation {
+        Animation::default()
+    }
+
+    pub fn push(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    /// This animation with each frame scaled by `factor`; see [`Frame::scaled`].
+    pub fn scaled(&self, factor: usize) -> Animation {
+        Animation {
+            frames: self
+                .frames
+                .iter()
+                .map(|frame| frame.scaled(factor))
+                .collect(),
+            delay: self.delay,
+            background: self.background,
+        }
+    }
+
+    /// Every frame drawn onto the smallest canvas containing them all, so that each grid
+    /// position is at the same pixel in every frame.
+    pub fn aligned(&self) -> Vec<Frame> {
+        let corners = self
+            .frames
+            .iter()
+            .filter(|frame| !frame.pixels.is_empty())
+            .flat_map(|frame| {
+                let size = Point::new(frame.width as i32, frame.height as i32);
+                vec![
+                    (frame.origin, Rgb::BLACK),
+                    (frame.origin + size - Point::new(1, 1), Rgb::BLACK),
+                ]
+            });
+        let canvas = Frame::from_points(corners, self.background);
+
+        self.frames
+            .iter()
+            .map(|frame| {
+                let mut aligned = canvas.clone();
+                for (idx, pixel) in frame.pixels.iter().enumerate() {
+                    let offset = Point::from((idx % frame.width, idx / frame.width));
+                    aligned.set(frame.origin + offset, *pixel);
+                }
+                aligned
+            })
+            .collect()
+    }
+
+    /// Write a looping animated GIF.
+    ///
+    /// The frames between them may use no more than 256 colours.
+    pub fn write_gif<W: Write>(&self, writer: W) -> Result<(), Error> {
+        let frames = self.aligned();
+        let (width, height) = match frames.first() {
+            Some(frame) if !frame.pixels.is_empty() => (frame.width, frame.height),
+            _ => return Err(Error::Empty),
+        };
+        if width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(Error::TooLarge(width, height));
+        }
+
+        let mut palette: HashMap<Rgb, u8> = HashMap::new();
+        let mut indexed = Vec::with_capacity(frames.len());
+        for frame in &frames {
+            let mut indices = Vec::with_capacity(frame.pixels.len());
+            for pixel in frame.rows().flatten() {
+                let next = palette.len();
+                let idx = match palette.get(pixel) {
+                    Some(idx) => *idx,
+                    None if next > 255 => return Err(Error::TooManyColours),
+                    None => *palette.entry(*pixel).or_insert(next as u8),
+                };
+                indices.push(idx);
+            }
+            indexed.push(indices);
+        }
+        let mut colours = vec![0; palette.len() * 3];
+        for (Rgb(r, g, b), idx) in palette {
+            let idx = idx as usize * 3;
+            colours[idx..idx + 3].copy_from_slice(&[r, g, b]);
+        }
+
+        let mut encoder = gif::Encoder::new(writer, width as u16, height as u16, &colours)?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for indices in indexed {
+            encoder.write_frame(&gif::Frame {
+                width: width as u16,
+                height: height as u16,
+                delay: self.delay,
+                buffer: Cow::Owned(indices),
+                ..gif::Frame::default()
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Write each aligned frame to `dir` as `frame-0000.<extension>`, `frame-0001.<extension>`,
+    /// and so on, creating the directory if necessary.
+    ///
+    /// `extension` is any format [`Frame::save`] understands.
+    pub fn save_frames(&self, dir: &Path, extension: &str) -> Result<Vec<PathBuf>, Error> {
+        std::fs::create_dir_all(dir)?;
+        self.aligned()
+            .iter()
+            .enumerate()
+            .map(|(idx, frame)| {
+                let path = dir.join(format!("frame-{:04}.{}", idx, extension));
+                frame.save(&path)?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    /// Write an animated GIF if the path ends in `.gif`, and otherwise a directory of numbered
+    /// PNG frames.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+        if extension.eq_ignore_ascii_case("gif") {
+            self.write_gif(BufWriter::new(File::create(path)?))
+        } else {
+            self.save_frames(path, "png").map(|_| ())
+        }
+    }
+}
+
+/// Nanoseconds spent drawing and storing frames.
+static FRAME_NANOS: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    static ref RECORDING: Mutex<Option<Animation>> = Mutex::new(None);
+}
+
+/// Start collecting the frames passed to [`frame`], discarding any collected so far.
+pub fn start_recording() {
+    *RECORDING.lock().unwrap() = Some(Animation::new());
+}
+
+/// Stop collecting frames, returning those collected since [`start_recording`].
+pub fn stop_recording() -> Option<Animation> {
+    RECORDING.lock().unwrap().take()
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.lock().unwrap().is_some()
+}
+
+/// Total time spent in [`frame`] since the last reset.
+pub fn frame_time() -> Duration {
+    Duration::from_nanos(FRAME_NANOS.load(Ordering::Relaxed))
+}
+
+/// Reset the counter behind [`frame_time`].
+pub fn reset_frame_time() {
+    FRAME_NANOS.store(0, Ordering::Relaxed);
+}
+
+/// Add a frame to the recording, if there is one.
+///
+/// `draw` is only called while recording, so solvers can call this freely. The recording
+/// isn't locked while drawing, so `draw` may itself use this module.
+pub fn frame<F>(draw: F)
+where
+    F: FnOnce() -> Frame,
+{
+    if !is_recording() {
+        return;
+    }
+    let start = Instant::now();
+    let frame = draw();
+    // recording may have stopped meanwhile, in which case the frame is dropped
+    if let Some(animation) = RECORDING.lock().unwrap().as_mut() {
+        animation.push(frame);
+    }
+    FRAME_NANOS.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("encoding png")]
+    Png(#[from] png::EncodingError),
+    #[error("encoding gif")]
+    Gif(#[from] gif::EncodingError),
+    #[error("nothing to draw")]
+    Empty,
+    #[error("{0}x{1} is too large for a gif")]
+    TooLarge(usize, usize),
+    #[error("gifs may have no more than 256 colours")]
+    TooManyColours,
+    #[error("unknown image format for {0}")]
+    UnknownFormat(PathBuf),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligned_frames() {
+        let mut animation = Animation::new();
+        animation.background = Rgb::WHITE;
+        animation.push(Frame::from_points(
+            vec![(Point::new(0, 0), Rgb::BLACK)],
+            Rgb::WHITE,
+        ));
+        animation.push(Frame::from_points(
+            vec![
+                (Point::new(-1, 0), Rgb::BLACK),
+                (Point::new(1, 1), Rgb::BLACK),
+            ],
+            Rgb::WHITE,
+        ));
+
+        let aligned = animation.scaled(2).aligned();
+        assert!(aligned
+            .iter()
+            .all(|frame| frame.origin() == Point::new(-2, 0)
+                && frame.width() == 6
+                && frame.height() == 4));
+        assert_eq!(aligned[0].get(Point::new(1, 1)), Some(Rgb::BLACK));
+        assert_eq!(aligned[0].get(Point::new(-1, 1)), Some(Rgb::WHITE));
+        assert_eq!(aligned[1].get(Point::new(-1, 1)), Some(Rgb::BLACK));
+
+        let mut ppm = Vec::new();
+        aligned[0].write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n6 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 6 * 4 * 3);
+        let mut gif = Vec::new();
+        animation.write_gif(&mut gif).unwrap();
+        let mut decoder = gif::DecodeOptions::new().read_info(gif.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (3, 2));
+        let mut frames = 0;
+        while decoder.read_next_frame().unwrap().is_some() {
+            frames += 1;
+        }
+        assert_eq!(frames, 2);
+    }
+
+    #[test]
+    fn test_reentrant_frames() {
+        let dot = || Frame::from_points(vec![(Point::new(0, 0), Rgb::BLACK)], Rgb::WHITE);
+        frame(|| panic!("drew a frame without recording"));
+
+        start_recording();
+        frame(|| {
+            // helpers shared with other code may check for, or add, frames themselves
+            assert!(is_recording());
+            frame(dot);
+            dot()
+        });
+        let animation = stop_recording().unwrap();
+        assert_eq!(animation.frames.len(), 2);
+        assert!(frame_time() > Duration::default());
+    }
+}
@@ -0,0 +1,373 @@
+use crate::config::Config;
+use crate::render;
+use color_eyre::eyre::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
+
+pub const YEAR: u16 = 2020;
+
+/// Command-line arguments shared by every day's binary.
+#[derive(StructOpt, Debug)]
+pub struct RunArgs {
+    /// input file
+    #[structopt(parse(from_os_str))]
+    pub input: Option<PathBuf>,
+
+    /// skip part 1
+    #[structopt(long = "no-part1")]
+    pub no_part1: bool,
+
+    /// run part 2
+    #[structopt(long)]
+    pub part2: bool,
+
+    /// output format: human, json or tsv
+    #[structopt(long, default_value = "human")]
+    pub format: Format,
+
+    /// log solver progress to stderr; repeat for more detail
+    #[structopt(short, long, parse(from_occurrences))]
+    pub verbose: u8,
+
+    /// write a chrome trace (viewable in chrome://tracing or Perfetto) to this file
+    #[structopt(long, parse(from_os_str))]
+    pub trace: Option<PathBuf>,
+
+    /// render each part's simulation, for days which support it, as a gif if this ends in .gif
+    /// and otherwise as a directory of numbered png frames; the part number is appended to the
+    /// name
+    #[structopt(long, parse(from_os_str))]
+    pub render: Option<PathBuf>,
+
+    /// width and height in pixels of each rendered grid tile
+    #[structopt(long, default_value = "4")]
+    pub render_scale: usize,
+}
+
+impl RunArgs {
+    pub fn input(&self, day: u8) -> Result<PathBuf> {
+        match self.input {
+            None => {
+                let config = Config::load()?;
+                Ok(config.input_for(day))
+            }
+            Some(ref path) => Ok(path.clone()),
+        }
+    }
+}
+
+/// How results are written to stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A sentence per answer.
+    Human,
+    /// One JSON object per line, per answer.
+    Json,
+    /// A header line, then one tab-separated line per answer.
+    Tsv,
+}
+
+/// Install a global tracing subscriber.
+///
+/// `verbosity` 0 logs only warnings, 1 adds info and debug, and 2 or more everything. If `trace` is set,
+/// spans and events at debug level and above are also written there as a chrome trace; the
+/// returned guard must be held until the program exits so that the file is flushed.
+pub fn install_tracing(verbosity: u8, trace: Option<&Path>) -> Option<FlushGuard> {
+    let level = match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let log = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_filter(level);
+
+    let (chrome, guard) = match trace {
+        Some(path) => {
+            let (chrome, guard) = ChromeLayerBuilder::new().file(path).build();
+            (
+                Some(chrome.with_filter(level.max(LevelFilter::DEBUG))),
+                Some(guard),
+            )
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry().with(log).with(chrome).init();
+    guard
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "human" => Ok(Format::Human),
+            "json" => Ok(Format::Json),
+            "tsv" => Ok(Format::Tsv),
+            unknown => Err(format!("unknown format: {}", unknown)),
+        }
+    }
+}
+
+/// The outcome of running a single part of a single day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    /// Microseconds spent reading and parsing input via [`crate::input`].
+    pub parse_time_us: u64,
+    /// Microseconds spent in the part, excluding `parse_time_us` and time spent drawing frames
+    /// for `--render`.
+    pub solve_time_us: u64,
+    /// See [`checksum`].
+    pub input_checksum: String,
+}
+
+impl Record {
+    pub const TSV_HEADER: &'static str =
+        "year\tday\tpart\tanswer\tparse_time_us\tsolve_time_us\tinput_checksum";
+
+    pub fn to_tsv(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.year,
+            self.day,
+            self.part,
+            // answers are never expected to contain tabs, but must never break the row
+            self.answer.replace(['\t', '\n'], " "),
+            self.parse_time_us,
+            self.solve_time_us,
+            self.input_checksum,
+        )
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("records are always serializable")
+    }
+
+    pub fn parse_time(&self) -> Duration {
+        Duration::from_micros(self.parse_time_us)
+    }
+
+    pub fn solve_time(&self) -> Duration {
+        Duration::from_micros(self.solve_time_us)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let part = match self.part {
+            1 => "one",
+            2 => "two",
+            _ => "?",
+        };
+        write!(f, "The answer to part {} is {}", part, self.answer)
+    }
+}
+
+/// 64-bit FNV-1a hash of the input, in hex.
+///
+/// This is not cryptographic; it exists to tell at a glance whether two runs saw the same input.
+pub fn checksum(data: &[u8]) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = data.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    });
+    format!("{:016x}", hash)
+}
+
+/// Run a single part, timing it.
+pub fn run_part<A, E, F>(day: u8, part: u8, input: &Path, solve: F) -> Result<Record, E>
+where
+    A: fmt::Display,
+    F: FnOnce(&Path) -> Result<A, E>,
+    E: From<std::io::Error>,
+{
+    let span = tracing::info_span!("part", day, part);
+    let _enter = span.enter();
+    let input_checksum = checksum(&std::fs::read(input)?);
+
+    crate::input::reset_parse_time();
+    render::reset_frame_time();
+    let start = Instant::now();
+    let answer = solve(input)?;
+    // time spent drawing frames for --render is no part of solving
+    let elapsed = start.elapsed() - render::frame_time().min(start.elapsed());
+    let parse_time = crate::input::parse_time().min(elapsed);
+    tracing::info!(?elapsed, ?parse_time, "solved");
+
+    Ok(Record {
+        year: YEAR,
+        day,
+        part,
+        answer: answer.to_string(),
+        parse_time_us: parse_time.as_micros() as u64,
+        solve_time_us: (elapsed - parse_time).as_micros() as u64,
+        input_checksum,
+    })
+}
+
+/// Where to render `part` given the `--render` path: `out.gif` becomes `out-part1.gif`, and
+/// `frames` becomes `frames-part1`.
+fn render_path(path: &Path, part: u8) -> PathBuf {
+    let mut name = path.file_stem().unwrap_or_default().to_owned();
+    name.push(format!("-part{}", part));
+    if let Some(extension) = path.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+    path.with_file_name(name)
+}
+
+/// Run a part, recording and saving any frames it renders if `--render` was given.
+fn run_rendered<T, E, F>(args: &RunArgs, part: u8, run: F) -> Result<T>
+where
+    E: 'static + std::error::Error + Send + Sync,
+    F: FnOnce() -> Result<T, E>,
+{
+    let path = match args.render {
+        None => return Ok(run()?),
+        Some(ref path) => render_path(path, part),
+    };
+    render::start_recording();
+    let out = run();
+    let animation = render::stop_recording().unwrap_or_default();
+    let out = out?;
+    if animation.frames.is_empty() {
+        tracing::warn!(part, "nothing was rendered");
+    } else {
+        animation.scaled(args.render_scale.max(1)).save(&path)?;
+        tracing::info!(part, frames = animation.frames.len(), path = %path.display(), "rendered");
+    }
+    Ok(out)
+}
+
+/// Entry point for a day's binary.
+///
+/// Parses the command line, runs the requested parts, and emits their results.
+pub fn run<A, B, E1, E2, P1, P2>(day: u8, part1: P1, part2: P2) -> Result<()>
+where
+    A: fmt::Display,
+    B: fmt::Display,
+    E1: 'static + std::error::Error + Send + Sync + From<std::io::Error>,
+    E2: 'static + std::error::Error + Send + Sync + From<std::io::Error>,
+    P1: FnOnce(&Path) -> Result<A, E1>,
+    P2: FnOnce(&Path) -> Result<B, E2>,
+{
+    run_with_args(day, RunArgs::from_args(), part1, part2)
+}
+
+/// Like [`run`], for days with options of their own: their binaries parse a struct which
+/// flattens [`RunArgs`] alongside those options, and pass the shared arguments on here.
+pub fn run_with_args<A, B, E1, E2, P1, P2>(
+    day: u8,
+    args: RunArgs,
+    part1: P1,
+    part2: P2,
+) -> Result<()>
+where
+    A: fmt::Display,
+    B: fmt::Display,
+    E1: 'static + std::error::Error + Send + Sync + From<std::io::Error>,
+    E2: 'static + std::error::Error + Send + Sync + From<std::io::Error>,
+    P1: FnOnce(&Path) -> Result<A, E1>,
+    P2: FnOnce(&Path) -> Result<B, E2>,
+{
+    color_eyre::install()?;
+    let _guard = install_tracing(args.verbose, args.trace.as_deref());
+    let input_path = args.input(day)?;
+    if !input_path.exists() {
+        bail!("input file {} does not exist", input_path.display());
+    }
+
+    let emit = |record: Record| match args.format {
+        Format::Human => println!("{}", record),
+        Format::Json => println!("{}", record.to_json()),
+        Format::Tsv => println!("{}", record.to_tsv()),
+    };
+
+    if args.format == Format::Tsv {
+        println!("{}", Record::TSV_HEADER);
+    }
+    if !args.no_part1 {
+        emit(run_rendered(&args, 1, || {
+            run_part(day, 1, &input_path, part1)
+        })?);
+    }
+    if args.part2 {
+        emit(run_rendered(&args, 2, || {
+            run_part(day, 2, &input_path, part2)
+        })?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(answer: &str) -> Record {
+        Record {
+            year: YEAR,
+            day: 7,
+            part: 2,
+            answer: answer.to_string(),
+            parse_time_us: 15,
+            solve_time_us: 1234,
+            input_checksum: checksum(b"a"),
+        }
+    }
+
+    #[test]
+    fn test_record_formats() {
+        let record = sample("126");
+        assert_eq!(record.to_string(), "The answer to part two is 126");
+        assert_eq!(
+            record.to_tsv(),
+            "2020\t7\t2\t126\t15\t1234\taf63dc4c8601ec8c"
+        );
+        assert_eq!(
+            Record::TSV_HEADER.split('\t').count(),
+            record.to_tsv().split('\t').count()
+        );
+        assert_eq!(
+            record.to_json(),
+            r#"{"year":2020,"day":7,"part":2,"answer":"126","parse_time_us":15,"solve_time_us":1234,"input_checksum":"af63dc4c8601ec8c"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<Record>(&record.to_json()).unwrap(),
+            record
+        );
+        assert_eq!(record.solve_time(), Duration::from_micros(1234));
+
+        // multi-line answers stay on one row
+        assert_eq!(sample("a\tb\nc").to_tsv().split('\t').nth(3), Some("a b c"));
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(b""), "cbf29ce484222325");
+        assert_eq!(checksum(b"a"), "af63dc4c8601ec8c");
+        assert_ne!(checksum(b"1\n2\n"), checksum(b"2\n1\n"));
+    }
+
+    #[test]
+    fn test_format_from_str() {
+        assert_eq!("json".parse::<Format>(), Ok(Format::Json));
+        assert_eq!("TSV".parse::<Format>(), Ok(Format::Tsv));
+        assert_eq!("Human".parse::<Format>(), Ok(Format::Human));
+        assert!("yaml".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,468 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::ops::Add;
+
+/// Anything which can be summed along a path and compared: `u32`, `usize`, `i64`, ...
+///
+/// `Default` must be zero.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T> Cost for T where T: Copy + Ord + Default + Add<Output = T> {}
+
+/// A route through a state space from a start to a goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<S, C> {
+    /// Every state from the start to the goal, inclusive.
+    pub states: Vec<S>,
+    /// The sum of the costs of every transition.
+    pub cost: C,
+}
+
+impl<S, C> Solution<S, C> {
+    /// The number of transitions.
+    pub fn steps(&self) -> usize {
+        self.states.len() - 1
+    }
+
+    pub fn goal(&self) -> &S {
+        self.states
+            .last()
+            .expect("solutions include at least the start")
+    }
+}
+
+/// How a search remembers which states it has already seen, so as not to explore them again.
+pub trait Visited<S> {
+    /// Record a state, returning whether it was new.
+    fn insert(&mut self, state: &S) -> bool;
+}
+
+/// Remember every state exactly.
+#[derive(Debug, Clone)]
+pub struct AllStates<S>(HashSet<S>);
+
+impl<S> Default for AllStates<S> {
+    fn default() -> Self {
+        AllStates(HashSet::new())
+    }
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> for AllStates<S> {
+    fn insert(&mut self, state: &S) -> bool {
+        if self.0.contains(state) {
+            return false;
+        }
+        self.0.insert(state.clone());
+        true
+    }
+}
+
+/// Remember only a key derived from each state, treating states with the same key as the same.
+///
+/// This suits states which carry data that doesn't affect where they lead, such as a running
+/// total.
+pub struct ByKey<K, F> {
+    keys: HashSet<K>,
+    key: F,
+}
+
+impl<K, F> ByKey<K, F> {
+    pub fn new(key: F) -> ByKey<K, F> {
+        ByKey {
+            keys: HashSet::new(),
+            key,
+        }
+    }
+}
+
+impl<S, K, F> Visited<S> for ByKey<K, F>
+where
+    K: Eq + Hash,
+    F: Fn(&S) -> K,
+{
+    fn insert(&mut self, state: &S) -> bool {
+        self.keys.insert((self.key)(state))
+    }
+}
+
+/// Remember a 64-bit hash of each state.
+///
+/// This uses far less memory for large states, at a tiny risk that a collision prunes a state
+/// which was never actually seen.
+#[derive(Debug, Clone, Default)]
+pub struct Hashes(HashSet<u64>);
+
+impl<S: Hash> Visited<S> for Hashes {
+    fn insert(&mut self, state: &S) -> bool {
+        let mut hasher = DefaultHasher::new();
+        state.hash(&mut hasher);
+        self.0.insert(hasher.finish())
+    }
+}
+
+/// Remember nothing.
+///
+/// This is only suitable for state spaces which are trees, or DAGs small enough that exploring
+/// shared states repeatedly is cheap; a cycle makes the search run forever.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Tree;
+
+impl<S> Visited<S> for Tree {
+    fn insert(&mut self, _state: &S) -> bool {
+        true
+    }
+}
+
+/// Every state a search has discovered, with a link back to the state it was discovered from.
+struct Arena<S, C> {
+    nodes: Vec<(S, Option<usize>, C)>,
+}
+
+impl<S: Clone, C: Cost> Arena<S, C> {
+    fn new(start: S) -> Arena<S, C> {
+        Arena {
+            nodes: vec![(start, None, C::default())],
+        }
+    }
+
+    fn push(&mut self, state: S, parent: usize, step: C) -> usize {
+        let cost = self.nodes[parent].2 + step;
+        self.nodes.push((state, Some(parent), cost));
+        self.nodes.len() - 1
+    }
+
+    fn solution(&self, mut idx: usize) -> Solution<S, C> {
+        let cost = self.nodes[idx].2;
+        let mut states = vec![self.nodes[idx].0.clone()];
+        while let Some(parent) = self.nodes[idx].1 {
+            states.push(self.nodes[parent].0.clone());
+            idx = parent;
+        }
+        states.reverse();
+        Solution { states, cost }
+    }
+}
+
+/// Breadth-first search, finding a goal reachable in the fewest transitions.
+pub fn bfs<S, C, I, F, G>(start: S, successors: F, is_goal: G) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    bfs_with(start, successors, is_goal, AllStates::default())
+}
+
+/// Breadth-first search, remembering visited states with the given strategy.
+#[tracing::instrument(level = "debug", skip(start, successors, is_goal, visited))]
+pub fn bfs_with<S, C, I, F, G, V>(
+    start: S,
+    mut successors: F,
+    mut is_goal: G,
+    mut visited: V,
+) -> Option<Solution<S, C>>
+where
+    S: Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+    V: Visited<S>,
+{
+    visited.insert(&start);
+    let mut arena = Arena::new(start);
+    let mut queue = VecDeque::new();
+    queue.push_back(0);
+
+    while let Some(idx) = queue.pop_front() {
+        if is_goal(&arena.nodes[idx].0) {
+            tracing::debug!(explored = arena.nodes.len(), "found goal");
+            return Some(arena.solution(idx));
+        }
+        for (next, step) in successors(&arena.nodes[idx].0) {
+            if visited.insert(&next) {
+                queue.push_back(arena.push(next, idx, step));
+            }
+        }
+    }
+    tracing::debug!(explored = arena.nodes.len(), "no goal");
+    None
+}
+
+/// Depth-first search, finding some reachable goal; not necessarily the nearest.
+///
+/// Successors are explored in the order they are produced.
+pub fn dfs<S, C, I, F, G>(start: S, successors: F, is_goal: G) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    dfs_with(start, successors, is_goal, AllStates::default())
+}
+
+/// Depth-first search, remembering visited states with the given strategy.
+#[tracing::instrument(level = "debug", skip(start, successors, is_goal, visited))]
+pub fn dfs_with<S, C, I, F, G, V>(
+    start: S,
+    mut successors: F,
+    mut is_goal: G,
+    mut visited: V,
+) -> Option<Solution<S, C>>
+where
+    S: Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+    V: Visited<S>,
+{
+    visited.insert(&start);
+    let mut arena = Arena::new(start);
+    let mut stack = vec![0];
+
+    while let Some(idx) = stack.pop() {
+        if is_goal(&arena.nodes[idx].0) {
+            tracing::debug!(explored = arena.nodes.len(), "found goal");
+            return Some(arena.solution(idx));
+        }
+        let first = stack.len();
+        for (next, step) in successors(&arena.nodes[idx].0) {
+            if visited.insert(&next) {
+                stack.push(arena.push(next, idx, step));
+            }
+        }
+        // so that the first successor is popped first
+        stack[first..].reverse();
+    }
+    tracing::debug!(explored = arena.nodes.len(), "no goal");
+    None
+}
+
+/// Dijkstra's algorithm, finding a cheapest route to a goal.
+pub fn dijkstra<S, C, I, F, G>(start: S, successors: F, is_goal: G) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A*, finding a cheapest route to a goal.
+///
+/// `heuristic` estimates the remaining cost from a state to the nearest goal; if it ever
+/// overestimates, the route found may not be the cheapest.
+#[tracing::instrument(level = "debug", skip(start, successors, heuristic, is_goal))]
+pub fn astar<S, C, I, F, H, G>(
+    start: S,
+    mut successors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut best = HashMap::new();
+    best.insert(start.clone(), C::default());
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((heuristic(&start), C::default(), 0)));
+    let mut arena = Arena::new(start);
+
+    while let Some(Reverse((_, cost, idx))) = open.pop() {
+        let state = &arena.nodes[idx].0;
+        // stale entries for states since reached more cheaply are skipped rather than removed
+        if best.get(state).is_some_and(|known| cost > *known) {
+            continue;
+        }
+        if is_goal(state) {
+            tracing::debug!(explored = arena.nodes.len(), "found goal");
+            return Some(arena.solution(idx));
+        }
+        for (next, step) in successors(state) {
+            let tentative = cost + step;
+            if best.get(&next).is_none_or(|known| tentative < *known) {
+                best.insert(next.clone(), tentative);
+                let estimate = tentative + heuristic(&next);
+                open.push(Reverse((estimate, tentative, arena.push(next, idx, step))));
+            }
+        }
+    }
+    tracing::debug!(explored = arena.nodes.len(), "no goal");
+    None
+}
+
+/// The cheapest cost of reaching every state reachable from `start`.
+pub fn dijkstra_all<S, C, I, F>(start: S, mut successors: F) -> HashMap<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+{
+    let mut best = HashMap::new();
+    best.insert(start.clone(), C::default());
+    // the heap orders states by their index in order of discovery, so they needn't be ordered
+    let mut discovered = vec![start];
+    let mut open = BinaryHeap::new();
+    open.push(Reverse((C::default(), 0)));
+
+    while let Some(Reverse((cost, idx))) = open.pop() {
+        let state = &discovered[idx];
+        if best.get(state).is_some_and(|known| cost > *known) {
+            continue;
+        }
+        for (next, step) in successors(state) {
+            let tentative = cost + step;
+            if best.get(&next).is_none_or(|known| tentative < *known) {
+                best.insert(next.clone(), tentative);
+                open.push(Reverse((tentative, discovered.len())));
+                discovered.push(next);
+            }
+        }
+    }
+    best
+}
+
+/// Iterative deepening depth-first search, finding a goal reachable in the fewest transitions
+/// while only ever holding the current path in memory.
+///
+/// States already on the current path are not revisited, so cycles are safe. Gives up after
+/// `max_depth` transitions.
+#[tracing::instrument(level = "debug", skip(start, successors, is_goal))]
+pub fn iddfs<S, C, I, F, G>(
+    start: S,
+    mut successors: F,
+    mut is_goal: G,
+    max_depth: usize,
+) -> Option<Solution<S, C>>
+where
+    S: Clone + PartialEq,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    fn descend<S, C, I, F, G>(
+        path: &mut Vec<(S, C)>,
+        depth: usize,
+        successors: &mut F,
+        is_goal: &mut G,
+    ) -> bool
+    where
+        S: Clone + PartialEq,
+        C: Cost,
+        I: IntoIterator<Item = (S, C)>,
+        F: FnMut(&S) -> I,
+        G: FnMut(&S) -> bool,
+    {
+        let (state, cost) = path.last().expect("path is never empty").clone();
+        if is_goal(&state) {
+            return true;
+        }
+        if depth == 0 {
+            return false;
+        }
+        for (next, step) in successors(&state) {
+            if path.iter().any(|(on_path, _)| *on_path == next) {
+                continue;
+            }
+            path.push((next, cost + step));
+            if descend(path, depth - 1, successors, is_goal) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    let mut path = vec![(start, C::default())];
+    for depth in 0..=max_depth {
+        if descend(&mut path, depth, &mut successors, &mut is_goal) {
+            tracing::debug!(depth, "found goal");
+            let cost = path.last().expect("path is never empty").1;
+            return Some(Solution {
+                states: path.into_iter().map(|(state, _)| state).collect(),
+                cost,
+            });
+        }
+    }
+    tracing::debug!("no goal");
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// From n, either add 1 for 1 or double for 3; doubling only pays off from 3.
+    fn successors(n: &u32) -> Vec<(u32, u32)> {
+        vec![(n + 1, 1), (n * 2, 3)]
+    }
+
+    #[test]
+    fn test_fewest_steps() {
+        let expect = vec![1, 2, 4, 8, 9];
+        let found = bfs(1, successors, |n| *n == 9).unwrap();
+        assert_eq!(found.states, expect);
+        assert_eq!(found.cost, 8);
+        let found = iddfs(1, successors, |n| *n == 9, 10).unwrap();
+        assert_eq!(found.states, expect);
+        assert!(iddfs(1, successors, |n| *n == 9, 3).is_none());
+    }
+
+    #[test]
+    fn test_cheapest() {
+        let found = dijkstra(1, successors, |n| *n == 9).unwrap();
+        assert_eq!(found.states, vec![1, 2, 3, 4, 8, 9]);
+        assert_eq!(found.cost, 7);
+        assert_eq!(found.steps(), 5);
+        assert_eq!(found.goal(), &9);
+
+        let heuristic = |n: &u32| 9_u32.saturating_sub(*n).min(4);
+        let found = astar(1, successors, heuristic, |n| *n == 9).unwrap();
+        assert_eq!(found.cost, 7);
+
+        let costs = dijkstra_all(1, |n| successors(n).into_iter().filter(|(n, _)| *n <= 9));
+        assert_eq!(costs.len(), 9);
+        assert_eq!(costs[&9], 7);
+
+        // states need not be ordered
+        #[derive(Clone, PartialEq, Eq, Hash)]
+        struct Unordered(u32);
+        let costs = dijkstra_all(Unordered(1), |Unordered(n)| {
+            successors(n)
+                .into_iter()
+                .filter(|(n, _)| *n <= 9)
+                .map(|(n, cost)| (Unordered(n), cost))
+        });
+        assert_eq!(costs[&Unordered(9)], 7);
+    }
+
+    #[test]
+    fn test_visited_strategies() {
+        // count up a cycle of 5 states, carrying a running total which never repeats
+        let successors = |(n, total): &(u32, u32)| vec![(((n + 1) % 5, total + n), 1)];
+        let unreachable = |(n, _): &(u32, u32)| *n > 4;
+
+        let by_position = ByKey::new(|s: &(u32, u32)| s.0);
+        assert!(bfs_with((0, 0), successors, unreachable, by_position).is_none());
+        let cycle = |n: &u32| vec![((n + 1) % 5, 1)];
+        assert!(dfs_with(0, cycle, |n| *n > 4, Hashes::default()).is_none());
+        let found = dfs_with((0, 0), successors, |(n, _)| *n == 4, Tree).unwrap();
+        assert_eq!(found.goal(), &(4, 6));
+    }
+}
@@ -0,0 +1,511 @@
+//! Searching lists of numbers for entries, or runs of entries, which sum to a target.
+
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use thiserror::Error;
+
+/// How [`KSum::find`] searches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Sort the entries, then fix all but two and close in on the last pair from both ends.
+    ///
+    /// Takes `O(n log n + n^(k-1))` time and `O(n)` space.
+    TwoPointer,
+    /// Fix all but two, then look up each entry's complement among those already seen.
+    ///
+    /// Takes `O(n^(k-1))` time and `O(n)` space, but hashing is slower than walking pointers.
+    Hash,
+}
+
+/// A search for `k` entries of a list which sum to `target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KSum {
+    pub k: usize,
+    pub target: i64,
+    /// Whether a single entry may be counted more than once.
+    pub reuse: bool,
+    pub strategy: Strategy,
+}
+
+/// Entries found by [`KSum::find`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combination {
+    /// Ascending indices of the entries in the searched list; repeated only with reuse.
+    pub indices: Vec<usize>,
+    /// The entries at those indices.
+    pub entries: Vec<i64>,
+}
+
+impl Combination {
+    pub fn product(&self) -> i64 {
+        self.entries.iter().product()
+    }
+}
+
+impl KSum {
+    /// A search for `k` distinct entries summing to `target`, using the two pointer strategy.
+    pub fn new(k: usize, target: i64) -> KSum {
+        KSum {
+            k,
+            target,
+            reuse: false,
+            strategy: Strategy::TwoPointer,
+        }
+    }
+
+    pub fn with_reuse(self, reuse: bool) -> KSum {
+        KSum { reuse, ..self }
+    }
+
+    pub fn with_strategy(self, strategy: Strategy) -> KSum {
+        KSum { strategy, ..self }
+    }
+
+    /// Some `k` entries which sum to the target, if any do.
+    ///
+    /// Which of several combinations is found depends on the strategy.
+    pub fn find(&self, entries: &[i64]) -> Option<Combination> {
+        let mut indices = match self.strategy {
+            Strategy::TwoPointer => {
+                let mut order: Vec<usize> = (0..entries.len()).collect();
+                order.sort_by_key(|&idx| entries[idx]);
+                let sorted: Vec<i64> = order.iter().map(|&idx| entries[idx]).collect();
+                let mut chosen = Vec::with_capacity(self.k);
+                if !self.two_pointer(&sorted, 0, self.k, self.target, &mut chosen) {
+                    return None;
+                }
+                chosen.into_iter().map(|idx| order[idx]).collect()
+            }
+            Strategy::Hash => {
+                let mut chosen = Vec::with_capacity(self.k);
+                if !self.hash(entries, 0, self.k, self.target, &mut chosen) {
+                    return None;
+                }
+                chosen
+            }
+        };
+        indices.sort_unstable();
+        let entries = indices.iter().map(|&idx| entries[idx]).collect();
+        Some(Combination { indices, entries })
+    }
+
+    /// The index after `idx` at which the next choice may start.
+    fn next_start(&self, idx: usize) -> usize {
+        if self.reuse {
+            idx
+        } else {
+            idx + 1
+        }
+    }
+
+    /// Choose `k` indices from `start..` of `sorted` summing to `target`, pushing them onto
+    /// `chosen`; on failure, `chosen` is left as it was.
+    fn two_pointer(
+        &self,
+        sorted: &[i64],
+        start: usize,
+        k: usize,
+        target: i64,
+        chosen: &mut Vec<usize>,
+    ) -> bool {
+        match k {
+            0 => target == 0,
+            1 => match sorted[start.min(sorted.len())..].binary_search(&target) {
+                Ok(offset) => {
+                    chosen.push(start + offset);
+                    true
+                }
+                Err(_) => false,
+            },
+            2 => {
+                if sorted.len() <= start {
+                    return false;
+                }
+                let (mut low, mut high) = (start, sorted.len() - 1);
+                while low < high || (self.reuse && low == high) {
+                    match (sorted[low] as i128 + sorted[high] as i128).cmp(&(target as i128)) {
+                        std::cmp::Ordering::Equal => {
+                            chosen.extend([low, high]);
+                            return true;
+                        }
+                        std::cmp::Ordering::Less => low += 1,
+                        // high > low here, or the sum couldn't be too big after being too small
+                        std::cmp::Ordering::Greater if high == 0 => return false,
+                        std::cmp::Ordering::Greater => high -= 1,
+                    }
+                }
+                false
+            }
+            _ => {
+                for idx in start..sorted.len() {
+                    // skip repeats of a value already tried in this position
+                    if idx > start && sorted[idx] == sorted[idx - 1] {
+                        continue;
+                    }
+                    let rest = match target.checked_sub(sorted[idx]) {
+                        Some(rest) => rest,
+                        None => continue,
+                    };
+                    chosen.push(idx);
+                    if self.two_pointer(sorted, self.next_start(idx), k - 1, rest, chosen) {
+                        return true;
+                    }
+                    chosen.pop();
+                }
+                false
+            }
+        }
+    }
+
+    /// As [`two_pointer`](Self::two_pointer), for unsorted `entries`.
+    fn hash(
+        &self,
+        entries: &[i64],
+        start: usize,
+        k: usize,
+        target: i64,
+        chosen: &mut Vec<usize>,
+    ) -> bool {
+        match k {
+            0 => target == 0,
+            1 => match (start..entries.len()).find(|&idx| entries[idx] == target) {
+                Some(idx) => {
+                    chosen.push(idx);
+                    true
+                }
+                None => false,
+            },
+            2 => {
+                let mut seen = HashMap::new();
+                for (idx, &entry) in entries.iter().enumerate().skip(start) {
+                    if self.reuse {
+                        seen.entry(entry).or_insert(idx);
+                    }
+                    let complement = target.checked_sub(entry);
+                    if let Some(&other) = complement.and_then(|c| seen.get(&c)) {
+                        chosen.extend([other, idx]);
+                        return true;
+                    }
+                    seen.entry(entry).or_insert(idx);
+                }
+                false
+            }
+            _ => {
+                for idx in start..entries.len() {
+                    let rest = match target.checked_sub(entries[idx]) {
+                        Some(rest) => rest,
+                        None => continue,
+                    };
+                    chosen.push(idx);
+                    if self.hash(entries, self.next_start(idx), k - 1, rest, chosen) {
+                        return true;
+                    }
+                    chosen.pop();
+                }
+                false
+            }
+        }
+    }
+}
+
+/// A search for contiguous runs of entries which sum to `target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeSum {
+    pub target: i64,
+    /// The fewest entries a run may have; never less than 1.
+    pub min_len: usize,
+}
+
+impl RangeSum {
+    /// A search for runs of at least one entry summing to `target`.
+    pub fn new(target: i64) -> RangeSum {
+        RangeSum { target, min_len: 1 }
+    }
+
+    pub fn with_min_len(self, min_len: usize) -> RangeSum {
+        RangeSum {
+            min_len: min_len.max(1),
+            ..self
+        }
+    }
+
+    /// The index ranges of every run summing to the target, ordered by end and then start.
+    pub fn all(&self, entries: &[i64]) -> Vec<Range<usize>> {
+        self.stream(entries.iter().copied()).collect()
+    }
+
+    /// The run with the fewest entries; the earliest of any tied.
+    pub fn shortest(&self, entries: &[i64]) -> Option<Range<usize>> {
+        self.stream(entries.iter().copied())
+            .min_by_key(|range| range.len())
+    }
+
+    /// The run with the most entries; the earliest of any tied.
+    pub fn longest(&self, entries: &[i64]) -> Option<Range<usize>> {
+        self.stream(entries.iter().copied())
+            .fold(None, |longest: Option<Range<usize>>, range| match longest {
+                Some(longest) if longest.len() >= range.len() => Some(longest),
+                _ => Some(range),
+            })
+    }
+
+    /// The runs of `entries` summing to the target, each yielded as soon as its last entry has
+    /// been read, in the same order as [`all`](Self::all).
+    ///
+    /// Entries may be negative. This remembers a prefix sum per entry read; see
+    /// [`window`](Self::window) to search an endless stream of non-negative entries.
+    pub fn stream<I>(&self, entries: I) -> RangeStream<I::IntoIter>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        let mut prefixes = HashMap::new();
+        prefixes.insert(0, vec![0]);
+        RangeStream {
+            entries: entries.into_iter(),
+            search: *self,
+            prefixes,
+            sum: 0,
+            end: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// As [`stream`](Self::stream), but sliding a window along the entries, so that only the
+    /// entries of the current window are held.
+    ///
+    /// This only works if entries are never negative; the first that is ends the stream with
+    /// an error.
+    pub fn window<I>(&self, entries: I) -> WindowStream<I::IntoIter>
+    where
+        I: IntoIterator<Item = i64>,
+    {
+        WindowStream {
+            entries: entries.into_iter(),
+            search: *self,
+            window: VecDeque::new(),
+            start: 0,
+            sum: 0,
+            pending: VecDeque::new(),
+            failed: false,
+        }
+    }
+}
+
+/// The iterator returned by [`RangeSum::stream`].
+#[derive(Debug, Clone)]
+pub struct RangeStream<I> {
+    entries: I,
+    search: RangeSum,
+    /// The indices at which each sum of the entries before them occurs, ascending.
+    prefixes: HashMap<i128, Vec<usize>>,
+    sum: i128,
+    end: usize,
+    pending: VecDeque<Range<usize>>,
+}
+
+impl<I: Iterator<Item = i64>> Iterator for RangeStream<I> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        while self.pending.is_empty() {
+            self.sum += self.entries.next()? as i128;
+            self.end += 1;
+            let (end, min_len) = (self.end, self.search.min_len);
+            if let Some(starts) = self.prefixes.get(&(self.sum - self.search.target as i128)) {
+                self.pending.extend(
+                    starts
+                        .iter()
+                        .take_while(|&&start| end - start >= min_len)
+                        .map(|&start| start..end),
+                );
+            }
+            self.prefixes.entry(self.sum).or_default().push(end);
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// A negative entry in the input to [`RangeSum::window`].
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("entry {index} is negative: {value}")]
+pub struct NegativeEntry {
+    pub index: usize,
+    pub value: i64,
+}
+
+/// The iterator returned by [`RangeSum::window`].
+#[derive(Debug, Clone)]
+pub struct WindowStream<I> {
+    entries: I,
+    search: RangeSum,
+    /// The longest run ending at the last entry read whose sum doesn't exceed the target.
+    window: VecDeque<i64>,
+    /// The index of the window's first entry.
+    start: usize,
+    sum: i128,
+    pending: VecDeque<Range<usize>>,
+    failed: bool,
+}
+
+impl<I: Iterator<Item = i64>> Iterator for WindowStream<I> {
+    type Item = Result<Range<usize>, NegativeEntry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let target = self.search.target as i128;
+        while self.pending.is_empty() {
+            if self.failed {
+                return None;
+            }
+            let entry = self.entries.next()?;
+            let index = self.start + self.window.len();
+            if entry < 0 {
+                self.failed = true;
+                return Some(Err(NegativeEntry {
+                    index,
+                    value: entry,
+                }));
+            }
+            self.window.push_back(entry);
+            self.sum += entry as i128;
+            // a negative target empties the window, and no run can reach it
+            while self.sum > target {
+                let first = match self.window.pop_front() {
+                    Some(first) => first,
+                    None => break,
+                };
+                self.sum -= first as i128;
+                self.start += 1;
+            }
+
+            // every run ending here which hits the target differs only in leading zeros
+            let end = index + 1;
+            let mut sum = self.sum;
+            for (start, entry) in (self.start..).zip(self.window.iter()) {
+                if sum != target || end - start < self.search.min_len {
+                    break;
+                }
+                self.pending.push_back(start..end);
+                sum -= *entry as i128;
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRATEGIES: [Strategy; 2] = [Strategy::TwoPointer, Strategy::Hash];
+
+    #[test]
+    fn test_k_sum() {
+        let entries = [1721, 979, 366, 299, 675, 1456];
+        for &strategy in &STRATEGIES {
+            let pair = KSum::new(2, 2020)
+                .with_strategy(strategy)
+                .find(&entries)
+                .unwrap();
+            assert_eq!(pair.indices, vec![0, 3]);
+            assert_eq!(pair.entries, vec![1721, 299]);
+
+            let triple = KSum::new(3, 2020)
+                .with_strategy(strategy)
+                .find(&entries)
+                .unwrap();
+            assert_eq!(triple.indices, vec![1, 2, 4]);
+            assert_eq!(triple.product(), 241861950);
+
+            assert_eq!(
+                KSum::new(4, 2020).with_strategy(strategy).find(&entries),
+                None
+            );
+            assert_eq!(
+                KSum::new(0, 0).with_strategy(strategy).find(&entries),
+                Some(Combination {
+                    indices: vec![],
+                    entries: vec![],
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn test_k_sum_reuse_and_negatives() {
+        let entries = [5, -3, 8, 0];
+        for &strategy in &STRATEGIES {
+            let search = KSum::new(2, 10).with_strategy(strategy);
+            assert_eq!(search.find(&entries), None);
+            let pair = search.with_reuse(true).find(&entries).unwrap();
+            assert_eq!(pair.indices, vec![0, 0]);
+
+            let triple = KSum::new(3, 2)
+                .with_strategy(strategy)
+                .find(&entries)
+                .unwrap();
+            assert_eq!(triple.entries.iter().sum::<i64>(), 2);
+            assert_eq!(triple.indices.len(), 3);
+
+            let search = KSum::new(3, -9).with_strategy(strategy);
+            assert_eq!(search.find(&entries), None);
+            assert_eq!(
+                search.with_reuse(true).find(&entries).unwrap().entries,
+                vec![-3, -3, -3]
+            );
+        }
+    }
+
+    #[test]
+    fn test_range_sum() {
+        let entries = [
+            35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309,
+            576,
+        ];
+        let search = RangeSum::new(127);
+        assert_eq!(search.all(&entries), vec![2..6, 14..15]);
+        assert_eq!(search.shortest(&entries), Some(14..15));
+        assert_eq!(search.longest(&entries), Some(2..6));
+        assert_eq!(search.with_min_len(2).all(&entries), vec![2..6]);
+        assert!(RangeSum::new(1).all(&entries).is_empty());
+
+        let windowed: Result<Vec<_>, _> = search.window(entries.iter().copied()).collect();
+        assert_eq!(windowed.unwrap(), search.all(&entries));
+
+        // zeros and negatives give overlapping runs
+        let entries = [3, 0, -2, 2, 3, 0];
+        assert_eq!(
+            RangeSum::new(3).all(&entries),
+            vec![0..1, 0..2, 0..4, 1..5, 2..5, 4..5, 1..6, 2..6, 4..6]
+        );
+        assert_eq!(RangeSum::new(3).longest(&entries), Some(1..6));
+        assert_eq!(RangeSum::new(3).shortest(&entries), Some(0..1));
+        assert_eq!(
+            RangeSum::new(3)
+                .window(entries.iter().copied())
+                .collect::<Vec<_>>(),
+            vec![
+                Ok(0..1),
+                Ok(0..2),
+                Err(NegativeEntry {
+                    index: 2,
+                    value: -2
+                })
+            ]
+        );
+
+        // a negative target is never reached, but negative entries are still reported
+        assert!(RangeSum::new(-1).window(vec![1]).next().is_none());
+        assert_eq!(
+            RangeSum::new(-1).window(vec![1, -1]).collect::<Vec<_>>(),
+            vec![Err(NegativeEntry {
+                index: 1,
+                value: -1
+            })]
+        );
+
+        // streams work on endless input
+        let mut runs = RangeSum::new(6).window((0..).map(|n| n % 4));
+        assert_eq!(runs.next(), Some(Ok(0..4)));
+        assert_eq!(runs.nth(3), Some(Ok(2..6)));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::config::Config;
+use crate::workspace::{self, Invocation};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use path_absolutize::Absolutize;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+use std::time::Duration;
+use thiserror::Error;
+
+/// How long to wait for a burst of file-system events to settle before rebuilding.
+///
+/// Editors tend to emit several events per save (truncate, write, rename, chmod...).
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Options for a watch session.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub day: u8,
+    pub invocation: Invocation,
+    pub release: bool,
+}
+
+/// Watch a day's sources, example files and input, rebuilding and rerunning on every change.
+///
+/// This never returns unless the watcher itself fails.
+///
+/// File-system notifications use the platform's native facility (inotify on Linux),
+/// so no outside services are required.
+pub fn watch(config: &Config, options: &Options) -> Result<(), Error> {
+    let root = workspace::root()?;
+    let day_dir = root.join(workspace::day_name(options.day));
+    if !day_dir.is_dir() {
+        return Err(Error::NoSuchDay(options.day));
+    }
+    // notify reports absolute paths, so a relative input could never be recognized
+    let input = options
+        .invocation
+        .input
+        .clone()
+        .unwrap_or_else(|| config.input_for(options.day))
+        .absolutize()?
+        .into_owned();
+    let invocation = Invocation {
+        input: Some(input.clone()),
+        part2: options.invocation.part2 && workspace::has_part2(options.day),
+        ..options.invocation.clone()
+    };
+
+    let (tx, rx) = channel();
+    let mut watcher = RecommendedWatcher::new(tx, notify::Config::default())?;
+    // sources, recursively
+    watcher.watch(&day_dir.join("src"), RecursiveMode::Recursive)?;
+    // Cargo.toml and any example files kept beside it
+    watcher.watch(&day_dir, RecursiveMode::NonRecursive)?;
+    // editors often replace rather than modify a file, which would orphan a watch on the
+    // file itself, so we watch its parent and filter
+    if let Some(input_dir) = input.parent() {
+        if input_dir != day_dir {
+            watcher.watch(input_dir, RecursiveMode::NonRecursive)?;
+        }
+    }
+
+    let mut previous = None;
+    loop {
+        previous = Some(rerun(&root, options, &invocation, previous.as_deref()));
+        wait_for_change(&rx, |path| is_relevant(path, &day_dir, &input))?;
+    }
+}
+
+/// Whether a change to `path` should trigger a rerun: it's in the day's directory, or it's the
+/// input. All three paths must be absolute.
+fn is_relevant(path: &Path, day_dir: &Path, input: &Path) -> bool {
+    path.starts_with(day_dir)
+        || path.file_name() == input.file_name() && path.parent() == input.parent()
+}
+
+/// Block until a relevant file changes, then drain the remainder of the event burst.
+fn wait_for_change<F>(
+    rx: &Receiver<notify::Result<notify::Event>>,
+    is_relevant: F,
+) -> Result<(), Error>
+where
+    F: Fn(&Path) -> bool,
+{
+    let relevant = |event: notify::Event| {
+        !event.kind.is_access() && event.paths.iter().any(|path| is_relevant(path))
+    };
+
+    loop {
+        let event = rx.recv().map_err(|_| Error::Disconnected)??;
+        if relevant(event) {
+            break;
+        }
+    }
+    while rx.recv_timeout(DEBOUNCE).is_ok() {}
+    Ok(())
+}
+
+/// Rebuild and rerun the day, printing the results and a diff against the previous run.
+///
+/// Returns the lines of output, for comparison against the next run.
+/// Build and run failures are reported, not returned, so that the watch continues.
+fn rerun(
+    root: &Path,
+    options: &Options,
+    invocation: &Invocation,
+    previous: Option<&[String]>,
+) -> Vec<String> {
+    println!("--- day {} ---", options.day);
+    let output = workspace::build_day(root, options.day, options.release)
+        .and_then(|binary| invocation.run(&binary));
+    let output = match output {
+        Ok(output) => output,
+        Err(err) => {
+            println!("{}", err);
+            return previous.map(<[String]>::to_vec).unwrap_or_default();
+        }
+    };
+
+    let lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    for line in diff(previous, &lines) {
+        println!("{}", line);
+    }
+    lines
+}
+
+/// Compact line-by-line comparison of an output against the one before it.
+///
+/// Unchanged lines are indented, changed lines are marked with `~` and followed by their
+/// previous value, and new lines are marked with `+`.
+fn diff(previous: Option<&[String]>, current: &[String]) -> Vec<String> {
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return current.iter().map(|line| format!("  {}", line)).collect(),
+    };
+
+    let mut out = Vec::with_capacity(current.len());
+    for (idx, line) in current.iter().enumerate() {
+        match previous.get(idx) {
+            Some(old) if old == line => out.push(format!("  {}", line)),
+            Some(old) => {
+                out.push(format!("~ {}", line));
+                out.push(format!("    (was: {})", old));
+            }
+            None => out.push(format!("+ {}", line)),
+        }
+    }
+    for old in previous.iter().skip(current.len()) {
+        out.push(format!("- {}", old));
+    }
+    out
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Workspace(#[from] workspace::Error),
+    #[error("day {0} has not been initialized")]
+    NoSuchDay(u8),
+    #[error("watching for changes")]
+    Notify(#[from] notify::Error),
+    #[error("file watcher disconnected")]
+    Disconnected,
+    #[error("resolving the input path")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        let first = lines(&["part 1: 5", "part 2: 7"]);
+        assert_eq!(diff(None, &first), lines(&["  part 1: 5", "  part 2: 7"]));
+        assert_eq!(
+            diff(Some(&first), &first),
+            lines(&["  part 1: 5", "  part 2: 7"])
+        );
+
+        let second = lines(&["part 1: 5", "part 2: 8", "part 3: 1"]);
+        assert_eq!(
+            diff(Some(&first), &second),
+            lines(&[
+                "  part 1: 5",
+                "~ part 2: 8",
+                "    (was: part 2: 7)",
+                "+ part 3: 1"
+            ])
+        );
+        assert_eq!(
+            diff(Some(&second), &first[..1]),
+            lines(&["  part 1: 5", "- part 2: 8", "- part 3: 1"])
+        );
+    }
+
+    #[test]
+    fn test_is_relevant() {
+        let day_dir = Path::new("/aoc/day01");
+        let input = Path::new("/aoc/inputs/input-01.txt");
+        assert!(is_relevant(
+            Path::new("/aoc/day01/src/lib.rs"),
+            day_dir,
+            input
+        ));
+        assert!(is_relevant(input, day_dir, input));
+        assert!(!is_relevant(
+            Path::new("/aoc/inputs/input-02.txt"),
+            day_dir,
+            input
+        ));
+        assert!(!is_relevant(
+            Path::new("/elsewhere/input-01.txt"),
+            day_dir,
+            input
+        ));
+
+        // relative inputs are resolved before matching against notify's absolute paths
+        let relative = Path::new("input-01.txt").absolutize().unwrap();
+        let reported = std::env::current_dir().unwrap().join("input-01.txt");
+        assert!(is_relevant(&reported, day_dir, &relative));
+    }
+}
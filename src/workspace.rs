@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use thiserror::Error;
+
+/// The package name, and sub-directory, of a given day's crate.
+pub fn day_name(day: u8) -> String {
+    format!("day{:02}", day)
+}
+
+/// Whether a given day's puzzle has a second part; the last day's doesn't.
+pub fn has_part2(day: u8) -> bool {
+    day != 25
+}
+
+/// Find the root of the workspace.
+///
+/// Like `init`, this must be run from the root of this package.
+pub fn root() -> Result<PathBuf, Error> {
+    let current_dir = std::env::current_dir()?;
+    crate::day::ensure_correct_dir(&current_dir)?;
+    Ok(current_dir)
+}
+
+/// The directory into which cargo places its build artifacts.
+pub fn target_dir(root: &Path) -> PathBuf {
+    match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => root.join(dir),
+        None => root.join("target"),
+    }
+}
+
+/// Path to the compiled binary of a given day.
+///
+/// This does not ensure that the binary exists; see [`build_day`].
+pub fn day_binary(root: &Path, day: u8, release: bool) -> PathBuf {
+    let profile = if release { "release" } else { "debug" };
+    target_dir(root).join(profile).join(format!(
+        "{}{}",
+        day_name(day),
+        std::env::consts::EXE_SUFFIX
+    ))
+}
+
+/// Build a given day's crate, returning the path to its binary.
+///
+/// If the build fails, the compiler output is returned in the error.
+pub fn build_day(root: &Path, day: u8, release: bool) -> Result<PathBuf, Error> {
+    build_days(root, &[day], release).map(|mut binaries| binaries.remove(0))
+}
+
+/// Build several days' crates in a single cargo invocation, returning the paths to their binaries.
+///
+/// If the build fails, the compiler output is returned in the error.
+pub fn build_days(root: &Path, days: &[u8], release: bool) -> Result<Vec<PathBuf>, Error> {
+    let mut cargo = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo.current_dir(root).args(["build", "--quiet"]);
+    for day in days {
+        cargo.arg("--package").arg(day_name(*day));
+    }
+    if release {
+        cargo.arg("--release");
+    }
+
+    let output = cargo.output()?;
+    if !output.status.success() {
+        return Err(Error::Build(
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    Ok(days
+        .iter()
+        .map(|day| day_binary(root, *day, release))
+        .collect())
+}
+
+/// All days which have been initialized in this workspace, in order.
+pub fn days(root: &Path) -> Result<Vec<u8>, Error> {
+    let mut days = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        let day = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.strip_prefix("day"))
+            .and_then(|day| day.parse::<u8>().ok());
+        if let Some(day) = day {
+            if entry.path().join("Cargo.toml").exists() {
+                days.push(day);
+            }
+        }
+    }
+    days.sort_unstable();
+    Ok(days)
+}
+
+/// Which parts of a day to run, and on what input.
+#[derive(Debug, Clone, Default)]
+pub struct Invocation {
+    /// input file; the day's default input if unset
+    pub input: Option<PathBuf>,
+    pub part1: bool,
+    pub part2: bool,
+}
+
+impl Invocation {
+    /// Arguments to pass to a day binary to perform this invocation.
+    pub fn args(&self) -> Vec<std::ffi::OsString> {
+        let mut args = Vec::new();
+        if let Some(ref input) = self.input {
+            args.push(input.clone().into_os_string());
+        }
+        if !self.part1 {
+            args.push("--no-part1".into());
+        }
+        if self.part2 {
+            args.push("--part2".into());
+        }
+        args
+    }
+
+    /// Run a built day binary, capturing its output.
+    ///
+    /// A non-zero exit status is an error.
+    pub fn run(&self, binary: &Path) -> Result<Output, Error> {
+        let output = Command::new(binary).args(self.args()).output()?;
+        if !output.status.success() {
+            return Err(Error::Run(
+                binary.display().to_string(),
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
+        Ok(output)
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("not in the workspace root")]
+    WrongDir(#[from] crate::day::Error),
+    #[error("build failed:\n{0}")]
+    Build(String),
+    #[error("{0} failed:\n{1}")]
+    Run(String, String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invocation_args() {
+        let invocation = Invocation {
+            input: Some(PathBuf::from("input.txt")),
+            part1: true,
+            part2: true,
+        };
+        assert_eq!(invocation.args(), vec!["input.txt", "--part2"]);
+
+        let invocation = Invocation {
+            input: None,
+            part1: false,
+            part2: false,
+        };
+        assert_eq!(invocation.args(), vec!["--no-part1"]);
+    }
+
+    #[test]
+    fn test_days() {
+        let root = std::env::temp_dir().join(format!("aoc2020-days-{}", std::process::id()));
+        for name in &["day03", "day12", "day1x", "notes", "day07"] {
+            std::fs::create_dir_all(root.join(name)).unwrap();
+        }
+        for name in &["day03", "day12", "day1x"] {
+            std::fs::write(root.join(name).join("Cargo.toml"), "").unwrap();
+        }
+        let days = days(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        // day07 has no crate, and day1x isn't a day
+        assert_eq!(days.unwrap(), vec![3, 12]);
+        assert_eq!(day_name(3), "day03");
+        assert!(has_part2(24));
+        assert!(!has_part2(25));
+        assert!(day_binary(&root, 3, true)
+            .ends_with(format!("release/day03{}", std::env::consts::EXE_SUFFIX)));
+    }
+}